@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use zkp_chaum_pedersen::{
+    ChaumPedersen, Commitment, Group, ParamSet, Protocol, Prover, Response, Transcript,
+};
+
+// Honest transcripts to verify
+fn transcripts<G: Group>(zkp: &ChaumPedersen<G>, n: usize) -> Vec<Transcript<G>> {
+    (0..n)
+        .map(|_| {
+            let prover = Prover::new(zkp, zkp.generate_secret_key());
+            let (y1, y2) = prover.public_key();
+            let (Commitment { r1, r2 }, prover) = prover.commit();
+            let c = zkp.generate_random();
+            let Response { s } = prover.respond(&c);
+            let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+            Transcript {
+                y1,
+                y2,
+                r1,
+                r2,
+                s,
+                c,
+            }
+        })
+        .collect()
+}
+
+// verify_batch against calling verify on every transcript
+fn compare<G: Group>(c: &mut Criterion, name: &str, zkp: &ChaumPedersen<G>) {
+    let mut group = c.benchmark_group(name);
+    for n in [16, 64, 256] {
+        let batch = transcripts(zkp, n);
+        group.bench_with_input(BenchmarkId::new("verify", n), &batch, |b, batch| {
+            b.iter(|| {
+                batch
+                    .iter()
+                    .all(|t| zkp.verify(&t.y1, &t.y2, &t.r1, &t.r2, &t.s, &t.c).is_ok())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("verify_batch", n), &batch, |b, batch| {
+            b.iter(|| zkp.verify_batch(batch).is_ok())
+        });
+    }
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+    compare(c, "rfc5114-1024-160", &zkp);
+    let zkp = ParamSet::Rfc5114_2048_256.zkp().unwrap();
+    compare(c, "rfc5114-2048-256", &zkp);
+    #[cfg(feature = "ristretto")]
+    compare(
+        c,
+        "ristretto255",
+        &zkp_chaum_pedersen::RistrettoZKP::ristretto255(),
+    );
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use zkp_chaum_pedersen::{ChaumPedersen, Commitment, Group, ParamSet, Protocol, Prover, Response};
+
+// verify against the four separate exponentiations it used to do, with and
+// without fixed-base tables for alpha and beta
+fn compare<G: Group>(c: &mut Criterion, name: &str, zkp: &ChaumPedersen<G>) {
+    let group = &zkp.group;
+    let prover = Prover::new(zkp, zkp.generate_secret_key());
+    let (y1, y2) = prover.public_key();
+    let (Commitment { r1, r2 }, prover) = prover.commit();
+    let ch = zkp.generate_random();
+    let Response { s } = prover.respond(&ch);
+
+    let mut bench = c.benchmark_group(name);
+    bench.bench_function("separate", |b| {
+        b.iter(|| {
+            r1 == group.op(&group.exp(&zkp.alpha, &s), &group.exp(&y1, &ch))
+                && r2 == group.op(&group.exp(&zkp.beta, &s), &group.exp(&y2, &ch))
+        })
+    });
+    let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+    bench.bench_function("verify", |b| {
+        b.iter(|| zkp.verify(&y1, &y2, &r1, &r2, &s, &ch).is_ok())
+    });
+    bench.finish();
+}
+
+fn verify(c: &mut Criterion) {
+    for set in [ParamSet::Rfc5114_1024_160, ParamSet::Rfc5114_2048_256] {
+        let zkp = set.zkp().unwrap();
+        compare(c, set.id(), &zkp);
+        compare(
+            c,
+            &format!("{}-tables", set.id()),
+            &zkp.with_fixed_base_tables(),
+        );
+    }
+}
+
+criterion_group!(benches, verify);
+criterion_main!(benches);
@@ -0,0 +1,444 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::composition::{unchecked, CheckedStatement, Statement};
+use crate::fiat_shamir::{decode_fields, encode_fields};
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::{ChaumPedersen, Commitment, Nonce, Protocol, SecretKey, ZkpError};
+
+/// The prover's answer to an AND challenge c: one response s_i per
+/// statement, all to the same c.
+pub struct AndResponse<G: Group> {
+    pub s: Vec<G::Scalar>,
+}
+
+/// Non-interactive AND proof: one commitment per statement and the
+/// response.
+pub struct AndProof<G: Group> {
+    pub commitments: Vec<Commitment<G>>,
+    pub response: AndResponse<G>,
+}
+
+impl<G: Group> Clone for AndResponse<G> {
+    fn clone(&self) -> Self {
+        AndResponse { s: self.s.clone() }
+    }
+}
+
+impl<G: Group> fmt::Debug for AndResponse<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndResponse").field("s", &self.s).finish()
+    }
+}
+
+impl<G: Group> Clone for AndProof<G> {
+    fn clone(&self) -> Self {
+        AndProof {
+            commitments: self.commitments.clone(),
+            response: self.response.clone(),
+        }
+    }
+}
+
+impl<G: Group> fmt::Debug for AndProof<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndProof")
+            .field("commitments", &self.commitments)
+            .field("response", &self.response)
+            .finish()
+    }
+}
+
+impl<G: Group> PartialEq for AndProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.commitments == other.commitments && self.response.s == other.response.s
+    }
+}
+
+impl<G: Group> AndProof<G> {
+    // Length-prefixed r1_i || r2_i || s_i for every statement in turn
+    pub fn to_bytes(&self, group: &G) -> Vec<u8> {
+        let mut fields = Vec::with_capacity(3 * self.commitments.len());
+        for (Commitment { r1, r2 }, s) in self.commitments.iter().zip(&self.response.s) {
+            fields.push(group.element_to_bytes(r1));
+            fields.push(group.element_to_bytes(r2));
+            fields.push(group.scalar_to_bytes(s));
+        }
+        encode_fields(&fields)
+    }
+
+    // A proof for the given number of statements
+    pub fn from_bytes(group: &G, statements: usize, bytes: &[u8]) -> Result<AndProof<G>, ZkpError> {
+        let fields = decode_fields(bytes, 3 * statements).ok_or(ZkpError::Decoding("proof"))?;
+        let element = |bytes, name| {
+            group
+                .element_from_bytes(bytes)
+                .ok_or(ZkpError::Decoding(name))
+        };
+        let mut proof = AndProof {
+            commitments: Vec::with_capacity(statements),
+            response: AndResponse {
+                s: Vec::with_capacity(statements),
+            },
+        };
+        for statement in fields.chunks(3) {
+            proof.commitments.push(Commitment {
+                r1: element(statement[0], "r1")?,
+                r2: element(statement[1], "r2")?,
+            });
+            let s = group
+                .scalar_from_bytes(statement[2])
+                .ok_or(ZkpError::Decoding("s"))?;
+            proof.response.s.push(s);
+        }
+        Ok(proof)
+    }
+}
+
+impl<G: Group> ChaumPedersen<G> {
+    // c = H(group, alpha, beta, n, y1_i, y2_i and r1_i, r2_i for every
+    // statement, context)
+    pub fn and_challenge(
+        &self,
+        statements: &[Statement<G>],
+        commitments: &[Commitment<G>],
+        context: &[u8],
+    ) -> G::Scalar {
+        self.composition_challenge(b"zkp-chaum-pedersen and", statements, commitments, context)
+    }
+
+    // Prove knowledge of every secret in one message
+    pub fn prove_and_non_interactive(
+        &self,
+        secrets: &[SecretKey<G>],
+        context: &[u8],
+    ) -> Result<AndProof<G>, ZkpError> {
+        self.prove_and_non_interactive_with_rng(secrets, context, &mut rand::thread_rng())
+    }
+
+    pub fn prove_and_non_interactive_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        secrets: &[SecretKey<G>],
+        context: &[u8],
+        rng: &mut R,
+    ) -> Result<AndProof<G>, ZkpError> {
+        if secrets.is_empty() {
+            return Err(ZkpError::LengthMismatch("secrets"));
+        }
+        let statements: Vec<Statement<G>> = secrets.iter().map(|x| self.exponentiate(x)).collect();
+        let (commitments, nonces) = self.and_commit(secrets.len(), rng);
+        let c = self.and_challenge(&statements, &commitments, context);
+        let response = self.and_respond(&nonces, secrets, &c);
+        Ok(AndProof {
+            commitments,
+            response,
+        })
+    }
+
+    pub fn verify_and_non_interactive(
+        &self,
+        statements: &[CheckedStatement<G>],
+        proof: &AndProof<G>,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        let commitments = self.check_commitments(statements.len(), proof.commitments.clone())?;
+        let c = self.and_challenge(&unchecked(statements), &proof.commitments, context);
+        self.verify_and(statements, &commitments, &proof.response, &c)
+    }
+
+    // A fresh nonce and its commitment per statement
+    fn and_commit<R: RngCore + CryptoRng>(
+        &self,
+        statements: usize,
+        rng: &mut R,
+    ) -> (Vec<Commitment<G>>, Vec<Nonce<G>>) {
+        let nonces: Vec<Nonce<G>> = (0..statements).map(|_| self.generate_nonce(rng)).collect();
+        let commitments = nonces
+            .iter()
+            .map(|k| {
+                let (r1, r2) = self.commit(k);
+                Commitment { r1, r2 }
+            })
+            .collect();
+        (commitments, nonces)
+    }
+
+    // s_i = k_i - c * x_i for every statement, with the one c
+    fn and_respond(
+        &self,
+        nonces: &[Nonce<G>],
+        secrets: &[SecretKey<G>],
+        c: &G::Scalar,
+    ) -> AndResponse<G> {
+        AndResponse {
+            s: nonces
+                .iter()
+                .zip(secrets)
+                .map(|(k, x)| self.solve(k, c, x))
+                .collect(),
+        }
+    }
+
+    // Every statement must verify against the shared c. An empty AND would
+    // prove nothing, so it is rejected
+    fn verify_and(
+        &self,
+        statements: &[CheckedStatement<G>],
+        commitments: &[CheckedStatement<G>],
+        response: &AndResponse<G>,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        if statements.is_empty() {
+            return Err(ZkpError::LengthMismatch("statements"));
+        }
+        if response.s.len() != statements.len() {
+            return Err(ZkpError::LengthMismatch("s"));
+        }
+        let statements = statements.iter().zip(commitments).zip(&response.s);
+        for (((y1, y2), (r1, r2)), s) in statements {
+            self.verify(y1, y2, r1, r2, s, c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prover side of one interactive AND run: proves knowledge of several
+/// independent secrets, such as a device key and a password key, in a
+/// single round trip.
+///
+/// Every statement gets its own fresh nonce, drawn from the rng, and all
+/// of them are answered with the same challenge.
+pub struct AndProver<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    secrets: Vec<SecretKey<G>>,
+}
+
+/// An AND prover that sent its commitments and waits for the challenge.
+pub struct AndAwaitingChallenge<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    secrets: Vec<SecretKey<G>>,
+    nonces: Vec<Nonce<G>>,
+}
+
+impl<'a, G: Group> AndProver<'a, G> {
+    // Fails if there are no secrets
+    pub fn new(zkp: &'a ChaumPedersen<G>, secrets: Vec<SecretKey<G>>) -> Result<Self, ZkpError> {
+        if secrets.is_empty() {
+            return Err(ZkpError::LengthMismatch("secrets"));
+        }
+        Ok(AndProver { zkp, secrets })
+    }
+
+    // The (y1, y2) of every secret, in order, to register with the verifier
+    pub fn public_keys(&self) -> Vec<Statement<G>> {
+        self.secrets
+            .iter()
+            .map(|x| self.zkp.exponentiate(x))
+            .collect()
+    }
+
+    pub fn commit(self) -> (Vec<Commitment<G>>, AndAwaitingChallenge<'a, G>) {
+        self.commit_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+    ) -> (Vec<Commitment<G>>, AndAwaitingChallenge<'a, G>) {
+        let (commitments, nonces) = self.zkp.and_commit(self.secrets.len(), rng);
+        let next = AndAwaitingChallenge {
+            zkp: self.zkp,
+            secrets: self.secrets,
+            nonces,
+        };
+        (commitments, next)
+    }
+}
+
+impl<G: Group> AndAwaitingChallenge<'_, G> {
+    // Answer the challenge for every statement, wiping the nonces
+    pub fn respond(self, c: &G::Scalar) -> AndResponse<G> {
+        self.zkp.and_respond(&self.nonces, &self.secrets, c)
+    }
+}
+
+/// Verifier side of one interactive AND run against registered (y1, y2)
+/// pairs, all of which must be proven.
+pub struct AndVerifier<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    statements: Vec<CheckedStatement<G>>,
+}
+
+/// An AND verifier that issued its challenge and waits for the response.
+pub struct AndAwaitingResponse<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    statements: Vec<CheckedStatement<G>>,
+    commitments: Vec<CheckedStatement<G>>,
+    c: G::Scalar,
+}
+
+impl<'a, G: Group> AndVerifier<'a, G> {
+    pub fn new(zkp: &'a ChaumPedersen<G>, statements: Vec<CheckedStatement<G>>) -> Self {
+        AndVerifier { zkp, statements }
+    }
+
+    // Check the commitments, one per statement, and draw the one challenge
+    pub fn challenge(
+        self,
+        commitments: Vec<Commitment<G>>,
+    ) -> Result<(G::Scalar, AndAwaitingResponse<'a, G>), ZkpError> {
+        self.challenge_with_rng(commitments, &mut rand::thread_rng())
+    }
+
+    pub fn challenge_with_rng<R: RngCore + CryptoRng>(
+        self,
+        commitments: Vec<Commitment<G>>,
+        rng: &mut R,
+    ) -> Result<(G::Scalar, AndAwaitingResponse<'a, G>), ZkpError> {
+        let commitments = self
+            .zkp
+            .check_commitments(self.statements.len(), commitments)?;
+        let c = self.zkp.generate_random_with_rng(rng);
+        let next = AndAwaitingResponse {
+            zkp: self.zkp,
+            statements: self.statements,
+            commitments,
+            c: c.clone(),
+        };
+        Ok((c, next))
+    }
+}
+
+impl<G: Group> AndAwaitingResponse<'_, G> {
+    pub fn verify(self, response: &AndResponse<G>) -> Result<(), ZkpError> {
+        self.zkp
+            .verify_and(&self.statements, &self.commitments, response, &self.c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ModPGroup, ParamSet};
+
+    fn checked<G: Group>(
+        zkp: &ChaumPedersen<G>,
+        statements: Vec<Statement<G>>,
+    ) -> Vec<CheckedStatement<G>> {
+        statements
+            .into_iter()
+            .map(|(y1, y2)| {
+                (
+                    zkp.check_element("y1", y1).unwrap(),
+                    zkp.check_element("y2", y2).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    // One interactive and one non-interactive run, to cover every group
+    fn run<G: Group>(zkp: &ChaumPedersen<G>) {
+        let secrets = (0..3).map(|_| zkp.generate_secret_key()).collect();
+        let prover = AndProver::new(zkp, secrets).unwrap();
+        let verifier = AndVerifier::new(zkp, checked(zkp, prover.public_keys()));
+        let (commitments, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(commitments).unwrap();
+        assert_eq!(verifier.verify(&prover.respond(&c)), Ok(()));
+
+        let secrets: Vec<_> = (0..2).map(|_| zkp.generate_secret_key()).collect();
+        let public_keys = secrets.iter().map(|x| zkp.exponentiate(x)).collect();
+        let proof = zkp.prove_and_non_interactive(&secrets, b"device").unwrap();
+        assert_eq!(
+            zkp.verify_and_non_interactive(&checked(zkp, public_keys), &proof, b"device"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_and_modp() {
+        run(&ParamSet::Rfc5114_1024_160.zkp().unwrap());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_and_ristretto() {
+        run(&crate::RistrettoZKP::ristretto255());
+    }
+
+    // knowing all but one of the secrets is not enough
+    #[test]
+    fn test_missing_secret() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let secrets: Vec<_> = (0..3).map(|_| zkp.generate_secret_key()).collect();
+        let mut public_keys: Vec<_> = secrets.iter().map(|x| zkp.exponentiate(x)).collect();
+        public_keys[1] = zkp.exponentiate(&zkp.generate_secret_key());
+        let prover = AndProver::new(&zkp, secrets).unwrap();
+        let verifier = AndVerifier::new(&zkp, checked(&zkp, public_keys));
+        let (commitments, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(commitments).unwrap();
+        assert_eq!(
+            verifier.verify(&prover.respond(&c)),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    // Each response answers its own position, so the order of the
+    // statements matters
+    #[test]
+    fn test_bound_to_the_order() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let secrets: Vec<_> = (0..2).map(|_| zkp.generate_secret_key()).collect();
+        let public_keys: Vec<_> = secrets.iter().map(|x| zkp.exponentiate(x)).collect();
+        let mut statements = checked(&zkp, public_keys);
+        let proof = zkp.prove_and_non_interactive(&secrets, b"device").unwrap();
+        statements.swap(0, 1);
+        assert_eq!(
+            zkp.verify_and_non_interactive(&statements, &proof, b"device"),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_lengths() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let secrets: Vec<_> = (0..2).map(|_| zkp.generate_secret_key()).collect();
+        let public_keys = secrets.iter().map(|x| zkp.exponentiate(x)).collect();
+        let statements = checked(&zkp, public_keys);
+        let proof = zkp.prove_and_non_interactive(&secrets, b"device").unwrap();
+
+        assert_eq!(
+            zkp.verify_and_non_interactive(&statements[..1], &proof, b"device"),
+            Err(ZkpError::LengthMismatch("commitments"))
+        );
+        let mut short = proof.clone();
+        short.response.s.pop();
+        assert_eq!(
+            zkp.verify_and_non_interactive(&statements, &short, b"device"),
+            Err(ZkpError::LengthMismatch("s"))
+        );
+
+        let bytes = proof.to_bytes(&zkp.group);
+        assert_eq!(AndProof::from_bytes(&zkp.group, 2, &bytes), Ok(proof));
+        assert_eq!(
+            AndProof::<ModPGroup>::from_bytes(&zkp.group, 1, &bytes),
+            Err(ZkpError::Decoding("proof"))
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        assert_eq!(
+            AndProver::new(&zkp, Vec::new()).map(|_| ()),
+            Err(ZkpError::LengthMismatch("secrets"))
+        );
+        let verifier = AndVerifier::new(&zkp, Vec::new());
+        let (_, verifier) = verifier.challenge(Vec::new()).unwrap();
+        assert_eq!(
+            verifier.verify(&AndResponse { s: Vec::new() }),
+            Err(ZkpError::LengthMismatch("statements"))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::group::Group;
+use crate::{ChaumPedersen, GroupElement};
+
+/// One interactive run as the verifier saw it: the statement (y1, y2), the
+/// commitments (r1, r2), the challenge c and the response s.
+pub struct Transcript<G: Group> {
+    pub y1: GroupElement<G>,
+    pub y2: GroupElement<G>,
+    pub r1: GroupElement<G>,
+    pub r2: GroupElement<G>,
+    pub s: G::Scalar,
+    pub c: G::Scalar,
+}
+
+impl<G: Group> Clone for Transcript<G> {
+    fn clone(&self) -> Self {
+        Transcript {
+            y1: self.y1.clone(),
+            y2: self.y2.clone(),
+            r1: self.r1.clone(),
+            r2: self.r2.clone(),
+            s: self.s.clone(),
+            c: self.c.clone(),
+        }
+    }
+}
+
+impl<G: Group> fmt::Debug for Transcript<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transcript")
+            .field("y1", &self.y1)
+            .field("y2", &self.y2)
+            .field("r1", &self.r1)
+            .field("r2", &self.r2)
+            .field("s", &self.s)
+            .field("c", &self.c)
+            .finish()
+    }
+}
+
+/// A batch that did not verify, with the indices of the transcripts that
+/// fail on their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    pub invalid: Vec<usize>,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid transcripts at indices {:?}", self.invalid)
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+// Picks the (y, r) pair of one of the two equations out of a transcript
+type Select<G> = fn(&Transcript<G>) -> (&GroupElement<G>, &GroupElement<G>);
+
+impl<G: Group> ChaumPedersen<G> {
+    /// Verify many transcripts at once.
+    ///
+    /// Both verification equations are checked for a random linear
+    /// combination of the transcripts with two multi-exponentiations each,
+    /// which is much cheaper than four exponentiations per transcript. If
+    /// the combination fails, every transcript is verified on its own to
+    /// report which ones are wrong.
+    pub fn verify_batch(&self, transcripts: &[Transcript<G>]) -> Result<(), BatchError> {
+        self.verify_batch_with_rng(transcripts, &mut rand::thread_rng())
+    }
+
+    // The weights are drawn from rng. They must be unpredictable to whoever
+    // produced the transcripts
+    pub fn verify_batch_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        transcripts: &[Transcript<G>],
+        rng: &mut R,
+    ) -> Result<(), BatchError> {
+        // as in `verify`, and the weighted sums below need scalars of this q
+        let out_of_range: Vec<usize> = transcripts
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !self.group.scalar_in_range(&t.s) || !self.group.scalar_in_range(&t.c))
+            .map(|(i, _)| i)
+            .collect();
+        if !out_of_range.is_empty() {
+            return Err(BatchError {
+                invalid: out_of_range,
+            });
+        }
+
+        if transcripts.is_empty()
+            || (self.check_combination(&self.alpha, transcripts, |t| (&t.y1, &t.r1), rng)
+                && self.check_combination(&self.beta, transcripts, |t| (&t.y2, &t.r2), rng))
+        {
+            return Ok(());
+        }
+
+        let invalid: Vec<usize> = transcripts
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.verify(&t.y1, &t.y2, &t.r1, &t.r2, &t.s, &t.c).is_err())
+            .map(|(i, _)| i)
+            .collect();
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(BatchError { invalid })
+        }
+    }
+
+    // With fresh random weights w_i, check
+    //   prod r_i^w_i = generator^(sum w_i * s_i) * prod y_i^(w_i * c_i)
+    // The weights are drawn like challenges, so a batch holding a transcript
+    // that does not verify passes with probability at most one over the size
+    // of the challenge space: 1 / challenge_bound, about 2^-128, for the mod p
+    // sets and 1 / q for the curves
+    fn check_combination<R: RngCore + CryptoRng>(
+        &self,
+        generator: &G::Element,
+        transcripts: &[Transcript<G>],
+        select: Select<G>,
+        rng: &mut R,
+    ) -> bool {
+        let group = &self.group;
+        let mut r_bases = Vec::with_capacity(transcripts.len());
+        let mut weights = Vec::with_capacity(transcripts.len());
+        let mut y_bases = vec![generator.clone()];
+        let mut y_exponents = Vec::with_capacity(transcripts.len() + 1);
+        let mut s_sum = None;
+
+        for transcript in transcripts {
+            let (y, r) = select(transcript);
+            let w = group.random_challenge(rng);
+            let ws = group.scalar_mul(&w, &transcript.s);
+            s_sum = Some(match s_sum {
+                Some(sum) => group.scalar_add(&sum, &ws),
+                None => ws,
+            });
+            y_bases.push(y.element().clone());
+            y_exponents.push(group.scalar_mul(&w, &transcript.c));
+            r_bases.push(r.element().clone());
+            weights.push(w);
+        }
+        y_exponents.insert(0, s_sum.expect("batch is not empty"));
+
+        group.multi_exp(&r_bases, &weights) == group.multi_exp(&y_bases, &y_exponents)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::modp::Scalar;
+    use crate::protocol::Sigma;
+    use crate::{ParamSet, Protocol, ZKP};
+
+    fn transcript<G: Group>(zkp: &ChaumPedersen<G>, honest: bool) -> Transcript<G> {
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+        let c = zkp.generate_random();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = if honest {
+            zkp.solve(&k, &c, &x)
+        } else {
+            zkp.solve(&k, &c, &zkp.generate_secret_key())
+        };
+        Transcript {
+            y1,
+            y2,
+            r1,
+            r2,
+            s,
+            c,
+        }
+    }
+
+    fn check_batch<G: Group>(zkp: &ChaumPedersen<G>) {
+        assert_eq!(zkp.verify_batch(&[]), Ok(()));
+
+        let mut transcripts: Vec<_> = (0..10).map(|_| transcript(zkp, true)).collect();
+        assert_eq!(zkp.verify_batch(&transcripts), Ok(()));
+
+        transcripts[3] = transcript(zkp, false);
+        transcripts[7] = transcript(zkp, false);
+        assert_eq!(
+            zkp.verify_batch(&transcripts),
+            Err(BatchError {
+                invalid: vec![3, 7]
+            })
+        );
+
+        // only the second equation broken
+        let mut transcripts: Vec<_> = (0..4).map(|_| transcript(zkp, true)).collect();
+        transcripts[0].r2 = transcripts[1].r2.clone();
+        assert_eq!(
+            zkp.verify_batch(&transcripts),
+            Err(BatchError { invalid: vec![0] })
+        );
+    }
+
+    #[test]
+    fn test_multi_exp_1024_bits() {
+        let (alpha, beta, p, q, challenge_bound) = ZKP::get_1024_bits_config().unwrap();
+        let zkp = ZKP::new(alpha, beta, p, q, challenge_bound);
+        let group = &zkp.group;
+
+        let bases = [zkp.alpha.clone(), zkp.beta.clone(), zkp.alpha.clone()];
+        let exponents = [
+            group.random_scalar(&mut rand::thread_rng()),
+            group.random_scalar(&mut rand::thread_rng()),
+            group.scalar(&group.q - 1u32),
+        ];
+        let expected = bases
+            .iter()
+            .zip(&exponents)
+            .fold(group.identity(), |acc, (b, e)| {
+                group.op(&acc, &group.exp(b, e))
+            });
+        assert_eq!(group.multi_exp(&bases, &exponents), expected);
+        assert_eq!(group.multi_exp(&[], &[]), group.identity());
+    }
+
+    // a scalar mod another q is reported, not a panic in the weighted sums
+    #[test]
+    fn test_scalar_out_of_range() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let other_q = &zkp.group.q + 2u32;
+        let mut transcripts: Vec<_> = (0..3).map(|_| transcript(&zkp, true)).collect();
+        transcripts[1].s = Scalar::new(transcripts[1].s.value().clone(), &other_q);
+        transcripts[2].c = Scalar::new(transcripts[2].c.value().clone(), &other_q);
+        assert_eq!(
+            zkp.verify_batch(&transcripts),
+            Err(BatchError {
+                invalid: vec![1, 2]
+            })
+        );
+    }
+
+    #[test]
+    fn test_batch_1024_bits() {
+        let (alpha, beta, p, q, challenge_bound) = ZKP::get_1024_bits_config().unwrap();
+        check_batch(&ZKP::new(alpha, beta, p, q, challenge_bound));
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_batch_ristretto() {
+        check_batch(&crate::RistrettoZKP::ristretto255());
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_batch_p256() {
+        check_batch(&crate::P256ZKP::p256());
+    }
+}
@@ -1,3 +1,4 @@
+use std::error::Error;
 use std::io::stdin;
 use std::thread;
 use std::time::Duration;
@@ -5,99 +6,145 @@ use std::time::Duration;
 pub mod zkp_auth {
     include!("./zkp_auth.rs");
 }
-use num_bigint::BigUint;
 use tonic::transport::Channel;
 use zkp_auth::auth_client::AuthClient;
 use zkp_auth::RegisterRequest;
 
-use zkp_chaum_pedersen::ZKP;
+use zkp_chaum_pedersen::{
+    modp::Scalar, password, Commitment, DeferredAwaitingChallenge, DeferredProver, Group,
+    ModPGroup, ParamSet, PasswordKdf, Prover, Response, SecretKey, ZkpError, ZKP,
+};
 
-use crate::zkp_auth::{AuthenticationChallengeRequest, AuthenticationAnswerRequest};
+use crate::zkp_auth::{AuthenticationAnswerRequest, AuthenticationChallengeRequest};
 
-// Create an instance of the ZKP struct
-fn zkp_instance() -> ZKP {
-    let (alpha, beta, p, q, rng_upper_bound) = ZKP::get_1024_bits_config();
-    ZKP::new(alpha, beta, p, q, rng_upper_bound)
+// Parameter set the client registers with
+const PARAM_SET: ParamSet = ParamSet::Rfc5114_1024_160;
+
+// Create an instance of the ZKP struct, with tables for alpha and beta
+fn zkp_instance() -> Result<ZKP, ZkpError> {
+    let zkp = PARAM_SET
+        .zkp()
+        .ok_or_else(|| ZkpError::UnsupportedGroup(PARAM_SET.id().to_string()))?;
+    Ok(zkp.with_fixed_base_tables())
+}
+
+// Derive the secret x from the password and the user's salt
+fn derive_secret(
+    zkp: &ZKP,
+    salt: &[u8],
+    password: &str,
+) -> Result<SecretKey<ModPGroup>, Box<dyn Error>> {
+    let x = PasswordKdf::default().derive_secret(
+        &zkp.group,
+        PARAM_SET.id(),
+        salt,
+        password.as_bytes(),
+    )?;
+    Ok(x)
 }
 
 // Entry point for the client application
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let mut buf = String::new();
     let domain_addr = String::from("https://127.0.0.1:50051");
-    let mut client = AuthClient::connect(domain_addr.clone())
-        .await
-        .expect("Failed to connect to the server");
+    let mut client = AuthClient::connect(domain_addr.clone()).await?;
 
     println!("Connnected to : {domain_addr}");
     println!("Username: ");
 
-    stdin()
-        .read_line(&mut buf)
-        .expect("Couldnt read username from stdin");
+    stdin().read_line(&mut buf)?;
 
     let user_name = buf.trim().to_string();
-    println!("Password(x):");
-    stdin().read_line(&mut buf).expect("Invalid pasword");
-    let x = BigUint::from_bytes_be(buf.trim().as_bytes());
+    println!("Password:");
+    let mut password = String::new();
+    stdin().read_line(&mut password)?;
+    let password = password.trim().to_string();
     thread::sleep(Duration::from_secs(2));
 
     // register
-    let zkp = zkp_instance();
-    register(&mut client, &zkp, &user_name, &x).await;
+    let zkp = zkp_instance()?;
+    let salt = password::random_salt();
+    let prover = Prover::new(&zkp, derive_secret(&zkp, &salt, &password)?);
+    register(&mut client, &zkp, &user_name, &prover, &salt).await?;
     thread::sleep(Duration::from_secs(2));
-    
-    // requeste authentication challenge
-    let k: BigUint = zkp.generate_random();
-    let (auth_id, c) = authentication_challenge(&mut client, &zkp, &user_name, &k).await;
+
+    // requeste authentication challenge, committing before x is known
+    let (commitment, prover) = DeferredProver::new(&zkp).commit();
+    let (auth_id, c, salt) =
+        authentication_challenge(&mut client, &zkp, &user_name, &commitment).await?;
     thread::sleep(Duration::from_secs(2));
-    
-    // verify the solution
-    let s = zkp.solve(&k, &c, &x);
-    verify(&mut client, &auth_id, &s).await;
+
+    // verify the solution, x is derived again from the salt the server kept
+    let x = derive_secret(&zkp, &salt, &password)?;
+    verify(&mut client, &zkp, &auth_id, prover, &c, &x).await
 }
 
 // Function to send a registration request to the server
-async fn register(client:&mut AuthClient<Channel> ,zkp: &ZKP, user_name: &String, x: &BigUint) {
+async fn register(
+    client: &mut AuthClient<Channel>,
+    zkp: &ZKP,
+    user_name: &str,
+    prover: &Prover<'_, ModPGroup>,
+    salt: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    let (y1, y2) = prover.public_key();
     let request = RegisterRequest {
-        user_name: user_name.clone(),
-        y1: zkp.alpha.clone().modpow(x, &zkp.p).to_bytes_be(),
-        y2: zkp.beta.clone().modpow(x, &zkp.p).to_bytes_be(),
+        user_name: user_name.to_string(),
+        y1: zkp.group.element_to_bytes(&y1),
+        y2: zkp.group.element_to_bytes(&y2),
+        group: PARAM_SET.id().to_string(),
+        salt: salt.to_vec(),
     };
     println!("Sending RegisterRequest : {:#?}", request);
-    let _response = client.register(request).await.unwrap();
+    let _response = client.register(request).await?;
+    Ok(())
 }
 
 // Function to request an authentication challenge from the server
-async fn authentication_challenge(client:&mut AuthClient<Channel> ,zkp: &ZKP, user_name: &String, k: &BigUint) -> (String, BigUint) {
+async fn authentication_challenge(
+    client: &mut AuthClient<Channel>,
+    zkp: &ZKP,
+    user_name: &str,
+    commitment: &Commitment<ModPGroup>,
+) -> Result<(String, Scalar, Vec<u8>), Box<dyn Error>> {
     let request = AuthenticationChallengeRequest {
-        user_name: user_name.clone(),
-        r1: zkp.alpha.clone().modpow(k, &zkp.p).to_bytes_be(),
-        r2: zkp.beta.clone().modpow(k, &zkp.p).to_bytes_be(),
+        user_name: user_name.to_string(),
+        r1: zkp.group.element_to_bytes(&commitment.r1),
+        r2: zkp.group.element_to_bytes(&commitment.r2),
     };
     println!("Sending AuthenticationChallengeRequest : {:#?}", request);
-    let response = client
-        .create_authentication_challenge(request)
-        .await
-        .unwrap();
+    let response = client.create_authentication_challenge(request).await?;
     println!("AuthenticationChallengeResponse: {:#?}", response);
-    let response =  response.into_inner();
+    let response = response.into_inner();
     let auth_id = response.auth_id;
-    let c = BigUint::from_bytes_be(&response.c);
+    let c = zkp
+        .group
+        .scalar_from_bytes(&response.c)
+        .ok_or(ZkpError::Decoding("c"))?;
 
-    (auth_id, c)
+    Ok((auth_id, c, response.salt))
 }
 
 // Function to verify the user's solution with the server
-async fn verify(client:&mut AuthClient<Channel>, auth_id: &String, s: &BigUint) {
-    let request = AuthenticationAnswerRequest{
-        auth_id : auth_id.clone(),
-        s: s.clone().to_bytes_be()
+async fn verify(
+    client: &mut AuthClient<Channel>,
+    zkp: &ZKP,
+    auth_id: &str,
+    prover: DeferredAwaitingChallenge<'_, ModPGroup>,
+    c: &Scalar,
+    x: &SecretKey<ModPGroup>,
+) -> Result<(), Box<dyn Error>> {
+    let Response { s } = prover.respond(c, x);
+    let request = AuthenticationAnswerRequest {
+        auth_id: auth_id.to_string(),
+        s: zkp.group.scalar_to_bytes(&s),
     };
 
-    let response = client.verify_authentication(request).await.unwrap();
+    let response = client.verify_authentication(request).await?;
     let response = response.into_inner();
     let session_id = response.session_id;
 
     println!("Logged in, session_id : {session_id}");
-}
\ No newline at end of file
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::fiat_shamir::FiatShamir;
+use crate::group::Group;
+use crate::{ChaumPedersen, Commitment, GroupElement, Protocol, ZkpError};
+
+/// One registered (y1, y2) pair, as the prover knows it.
+pub type Statement<G> = (<G as Group>::Element, <G as Group>::Element);
+
+/// One registered (y1, y2) pair, checked by the verifier.
+pub type CheckedStatement<G> = (GroupElement<G>, GroupElement<G>);
+
+// The checked statements back as the elements the transcript hashes
+pub(crate) fn unchecked<G: Group>(statements: &[CheckedStatement<G>]) -> Vec<Statement<G>> {
+    statements
+        .iter()
+        .map(|(y1, y2)| (y1.element().clone(), y2.element().clone()))
+        .collect()
+}
+
+// What the OR and AND compositions of Chaum-Pedersen statements share: one
+// commitment per statement and a single challenge over all of them
+impl<G: Group> ChaumPedersen<G> {
+    // c = H(group, alpha, beta, n, y1_i, y2_i and r1_i, r2_i for every
+    // statement, context), under the composition's own label
+    pub(crate) fn composition_challenge(
+        &self,
+        label: &[u8],
+        statements: &[Statement<G>],
+        commitments: &[Commitment<G>],
+        context: &[u8],
+    ) -> G::Scalar {
+        let group = &self.group;
+        let mut transcript = FiatShamir::new(label);
+        transcript.append(&group.params_bytes());
+        transcript.append_element(group, &self.alpha);
+        transcript.append_element(group, &self.beta);
+        transcript.append(&(statements.len() as u64).to_be_bytes());
+        for (y1, y2) in statements {
+            transcript.append_element(group, y1);
+            transcript.append_element(group, y2);
+        }
+        for Commitment { r1, r2 } in commitments {
+            transcript.append_element(group, r1);
+            transcript.append_element(group, r2);
+        }
+        transcript.append(context);
+        transcript.challenge(group)
+    }
+
+    // One commitment per statement, each element checked
+    pub(crate) fn check_commitments(
+        &self,
+        statements: usize,
+        commitments: Vec<Commitment<G>>,
+    ) -> Result<Vec<CheckedStatement<G>>, ZkpError> {
+        if commitments.len() != statements {
+            return Err(ZkpError::LengthMismatch("commitments"));
+        }
+        commitments
+            .into_iter()
+            .map(|Commitment { r1, r2 }| {
+                Ok((self.check_element("r1", r1)?, self.check_element("r2", r2)?))
+            })
+            .collect()
+    }
+}
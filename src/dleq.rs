@@ -0,0 +1,381 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::fiat_shamir::{decode_fields, encode_fields, FiatShamir};
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::session::{AwaitingChallenge, AwaitingResponse, Prover, Verifier};
+use crate::{ChaumPedersen, GroupElement, NonceMode, Protocol, SecretKey, ZkpError};
+
+/// Proof that public values h_1..h_n share one discrete log x to bases
+/// g_1..g_n: h_i = g_i^x for every i.
+///
+/// Chaum-Pedersen is the case n = 2 with bases (alpha, beta), and Schnorr
+/// the case n = 1. The prover commits to r_i = g_i^k for every base and
+/// answers a challenge c with a single s = k - c * x mod q, which the
+/// verifier checks against r_i = g_i^s * h_i^c.
+#[derive(Debug, Clone)]
+pub struct Dleq<G: Group> {
+    pub group: G,
+    bases: Vec<G::Element>,
+}
+
+/// Non-interactive DLEQ proof: one commitment per base and the response.
+pub struct DleqProof<G: Group> {
+    pub r: Vec<G::Element>,
+    pub s: G::Scalar,
+}
+
+impl<G: Group> Clone for DleqProof<G> {
+    fn clone(&self) -> Self {
+        DleqProof {
+            r: self.r.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: Group> PartialEq for DleqProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r == other.r && self.s == other.s
+    }
+}
+
+impl<G: Group> fmt::Debug for DleqProof<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DleqProof")
+            .field("r", &self.r)
+            .field("s", &self.s)
+            .finish()
+    }
+}
+
+impl<G: Group> DleqProof<G> {
+    // Length-prefixed r_1 || .. || r_n || s, as for `Proof`
+    pub fn to_bytes(&self, group: &G) -> Vec<u8> {
+        let mut fields: Vec<Vec<u8>> = self.r.iter().map(|r| group.element_to_bytes(r)).collect();
+        fields.push(group.scalar_to_bytes(&self.s));
+        encode_fields(&fields)
+    }
+
+    // A proof for a statement with the given number of bases
+    pub fn from_bytes(group: &G, bases: usize, bytes: &[u8]) -> Result<DleqProof<G>, ZkpError> {
+        let mut fields = decode_fields(bytes, bases + 1).ok_or(ZkpError::Decoding("proof"))?;
+        let s = fields.pop().expect("bases + 1 fields");
+        Ok(DleqProof {
+            r: fields
+                .into_iter()
+                .map(|r| group.element_from_bytes(r).ok_or(ZkpError::Decoding("r")))
+                .collect::<Result<_, _>>()?,
+            s: group.scalar_from_bytes(s).ok_or(ZkpError::Decoding("s"))?,
+        })
+    }
+}
+
+impl<G: Group + Clone> ChaumPedersen<G> {
+    // The same statement as a DLEQ over (alpha, beta), to extend with more
+    // bases through `Dleq::new`
+    pub fn dleq(&self) -> Dleq<G> {
+        Dleq {
+            group: self.group.clone(),
+            bases: vec![self.alpha.clone(), self.beta.clone()],
+        }
+    }
+}
+
+impl<G: Group> Protocol<G> for Dleq<G> {
+    const LABEL: &'static [u8] = b"zkp-dleq";
+
+    type PublicKey<T> = Vec<T>;
+    type Commitment = Vec<G::Element>;
+
+    fn group(&self) -> &G {
+        &self.group
+    }
+
+    fn bases(&self) -> Vec<&G::Element> {
+        self.bases.iter().collect()
+    }
+
+    fn public_key_from<T>(h: Vec<T>) -> Vec<T> {
+        h
+    }
+
+    fn public_key_values<T>(h: Vec<T>) -> Vec<T> {
+        h
+    }
+
+    fn commitment_from(r: Vec<G::Element>) -> Vec<G::Element> {
+        r
+    }
+
+    fn check_commitment(&self, r: Vec<G::Element>) -> Result<Vec<GroupElement<G>>, ZkpError> {
+        if r.len() != self.bases.len() {
+            return Err(ZkpError::LengthMismatch("r"));
+        }
+        self.check_elements("r", r)
+    }
+
+    // The count goes first, so that no two statements share a prefix
+    fn statement(&self, label: &[u8], h: &[&G::Element]) -> FiatShamir {
+        let group = &self.group;
+        let mut transcript = FiatShamir::new(label);
+        transcript.append(&group.params_bytes());
+        transcript.append(&(self.bases.len() as u64).to_be_bytes());
+        for element in self.bases.iter().chain(h.iter().copied()) {
+            transcript.append_element(group, element);
+        }
+        transcript
+    }
+}
+
+impl<G: Group> Dleq<G> {
+    // Fails unless there is at least one base and every base is a
+    // non-identity element of the group
+    pub fn new(group: G, bases: Vec<G::Element>) -> Result<Self, ZkpError> {
+        if bases.is_empty() {
+            return Err(ZkpError::LengthMismatch("bases"));
+        }
+        for base in &bases {
+            GroupElement::new(&group, "g", base.clone())?;
+        }
+        Ok(Dleq { group, bases })
+    }
+
+    // Compute the public values h_i = g_i^x
+    pub fn public_values(&self, x: &SecretKey<G>) -> Vec<G::Element> {
+        self.exp_bases(x.expose())
+    }
+
+    /// verify that r_i = g_i^s * h_i^c for every base g_i
+    ///
+    /// There must be exactly one h_i and one r_i per base, and the response
+    /// s must be reduced mod q.
+    pub fn verify(
+        &self,
+        h: &[GroupElement<G>],
+        r: &[GroupElement<G>],
+        s: &G::Scalar,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        let h: Vec<&GroupElement<G>> = h.iter().collect();
+        let r: Vec<&GroupElement<G>> = r.iter().collect();
+        self.verify_values(&h, &r, s, c)
+    }
+
+    // Check every element of a list, naming them all alike
+    pub fn check_elements(
+        &self,
+        name: &'static str,
+        elements: Vec<G::Element>,
+    ) -> Result<Vec<GroupElement<G>>, ZkpError> {
+        elements
+            .into_iter()
+            .map(|element| self.check_element(name, element))
+            .collect()
+    }
+
+    // c = H(group, n, g_1..g_n, h_1..h_n, r_1..r_n, context), for an h and
+    // an r with one entry per base
+    pub fn challenge(&self, h: &[G::Element], r: &[G::Element], context: &[u8]) -> G::Scalar {
+        let h: Vec<&G::Element> = h.iter().collect();
+        let r: Vec<&G::Element> = r.iter().collect();
+        self.challenge_values(&h, &r, context)
+    }
+
+    // Prove that h_i = g_i^x for every base in one message
+    pub fn prove_non_interactive(&self, x: &SecretKey<G>, context: &[u8]) -> DleqProof<G> {
+        self.prove_non_interactive_with_rng(x, context, &mut rand::thread_rng())
+    }
+
+    pub fn prove_non_interactive_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> DleqProof<G> {
+        self.prove_non_interactive_with_mode(x, context, NonceMode::Random, rng)
+    }
+
+    pub fn prove_non_interactive_with_mode<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        mode: NonceMode,
+        rng: &mut R,
+    ) -> DleqProof<G> {
+        let (r, s) = self.prove_values(x, context, mode, rng);
+        DleqProof { r, s }
+    }
+
+    pub fn verify_non_interactive(
+        &self,
+        h: &[GroupElement<G>],
+        proof: &DleqProof<G>,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        let h: Vec<&GroupElement<G>> = h.iter().collect();
+        self.verify_proof(&h, proof.r.clone(), &proof.s, context)
+    }
+}
+
+/// Prover side of one interactive DLEQ run: `Prover` run over `Dleq`,
+/// committing to one r_i per base and answering with s.
+pub type DleqProver<'a, G> = Prover<'a, G, Dleq<G>>;
+
+/// A DLEQ prover that sent its commitments and waits for the challenge.
+pub type DleqAwaitingChallenge<'a, G> = AwaitingChallenge<'a, G, Dleq<G>>;
+
+/// Verifier side of one interactive DLEQ run against registered h_1..h_n.
+pub type DleqVerifier<'a, G> = Verifier<'a, G, Dleq<G>>;
+
+/// A DLEQ verifier that issued its challenge and waits for s.
+pub type DleqAwaitingResponse<'a, G> = AwaitingResponse<'a, G, Dleq<G>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ModPGroup, ParamSet};
+
+    // alpha, beta and two more bases with unknown logs to the verifier
+    fn four_bases<G: Group + Clone>(zkp: &ChaumPedersen<G>) -> Dleq<G> {
+        let mut rng = rand::thread_rng();
+        let mut bases = zkp.dleq().bases.clone();
+        for _ in 0..2 {
+            let log = zkp.group.random_scalar(&mut rng);
+            bases.push(zkp.group.exp(&zkp.alpha, &log));
+        }
+        Dleq::new(zkp.group.clone(), bases).unwrap()
+    }
+
+    // Interactive runs by the holder of x and by an impostor, and one
+    // non-interactive run, to cover every group
+    fn run<G: Group + Clone>(zkp: &ChaumPedersen<G>) {
+        let dleq = four_bases(zkp);
+        let prover = DleqProver::new(&dleq, dleq.generate_secret_key());
+        let h = dleq.check_elements("h", prover.public_key()).unwrap();
+        let run_session = |prover: DleqProver<'_, G>| {
+            let verifier = DleqVerifier::new(&dleq, h.clone());
+            let (r, prover) = prover.commit();
+            let (c, verifier) = verifier.challenge(r).unwrap();
+            verifier.verify(&prover.respond(&c))
+        };
+        assert_eq!(run_session(prover), Ok(()));
+        let impostor = DleqProver::new(&dleq, dleq.generate_secret_key());
+        assert_eq!(run_session(impostor), Err(ZkpError::VerificationFailed));
+
+        let x = dleq.generate_secret_key();
+        let h = dleq.check_elements("h", dleq.public_values(&x)).unwrap();
+        let proof = dleq.prove_non_interactive(&x, b"alice");
+        assert_eq!(dleq.verify_non_interactive(&h, &proof, b"alice"), Ok(()));
+    }
+
+    #[test]
+    fn test_dleq_modp() {
+        run(&ParamSet::Rfc5114_1024_160.zkp().unwrap());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_dleq_ristretto() {
+        run(&crate::RistrettoZKP::ristretto255());
+    }
+
+    // Knowing the log of every h_i is not enough, they must all be the same
+    #[test]
+    fn test_rejects_different_exponents() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let dleq = four_bases(&zkp);
+        let x = dleq.generate_secret_key();
+        let other = dleq.generate_secret_key();
+        let mut h = dleq.public_values(&x);
+        h[2] = dleq.public_values(&other).swap_remove(2);
+        let h = dleq.check_elements("h", h).unwrap();
+        let proof = dleq.prove_non_interactive(&x, b"alice");
+        assert_eq!(
+            dleq.verify_non_interactive(&h, &proof, b"alice"),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_lengths() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let dleq = four_bases(&zkp);
+        let x = dleq.generate_secret_key();
+        let h = dleq.check_elements("h", dleq.public_values(&x)).unwrap();
+        let proof = dleq.prove_non_interactive(&x, b"alice");
+
+        assert_eq!(
+            dleq.verify_non_interactive(&h[1..], &proof, b"alice"),
+            Err(ZkpError::LengthMismatch("h"))
+        );
+        let mut short = proof.clone();
+        short.r.pop();
+        assert_eq!(
+            dleq.verify_non_interactive(&h, &short, b"alice"),
+            Err(ZkpError::LengthMismatch("r"))
+        );
+        let verifier = DleqVerifier::new(&dleq, h.clone());
+        assert_eq!(
+            verifier.challenge(short.r.clone()).map(|_| ()),
+            Err(ZkpError::LengthMismatch("r"))
+        );
+        // a key with a value missing fails at the end, like a proof for it
+        let prover = DleqProver::new(&dleq, x);
+        let verifier = DleqVerifier::new(&dleq, h[1..].to_vec());
+        let (r, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(r).unwrap();
+        assert_eq!(
+            verifier.verify(&prover.respond(&c)),
+            Err(ZkpError::LengthMismatch("h"))
+        );
+
+        // the encoding does not carry the count, the verifier supplies it
+        let bytes = proof.to_bytes(&dleq.group);
+        assert_eq!(DleqProof::from_bytes(&dleq.group, 4, &bytes), Ok(proof));
+        assert_eq!(
+            DleqProof::<ModPGroup>::from_bytes(&dleq.group, 3, &bytes),
+            Err(ZkpError::Decoding("proof"))
+        );
+    }
+
+    // With one base this is a Schnorr proof, under its own label
+    #[test]
+    fn test_single_base() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let dleq = Dleq::new(zkp.group.clone(), vec![zkp.alpha.clone()]).unwrap();
+        let x = dleq.generate_secret_key();
+        let h = dleq.check_elements("h", dleq.public_values(&x)).unwrap();
+        assert_eq!(*h[0].element(), zkp.schnorr().public_key(&x));
+        let proof = dleq.prove_non_interactive(&x, b"alice");
+        assert_eq!(dleq.verify_non_interactive(&h, &proof, b"alice"), Ok(()));
+    }
+
+    #[test]
+    fn test_matches_chaum_pedersen() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let dleq = zkp.dleq();
+        let x = zkp.generate_secret_key();
+        let (y1, y2) = zkp.exponentiate(&x);
+        assert_eq!(dleq.public_values(&x), vec![y1, y2]);
+
+        // the interactive prover hedges Deterministic nonces
+        let commit = || {
+            let prover = DleqProver::new(&dleq, SecretKey::new(x.expose().clone()));
+            prover.with_nonce_mode(NonceMode::Deterministic).commit().0
+        };
+        assert_ne!(commit(), commit());
+
+        let group = zkp.group.clone();
+        assert_eq!(
+            Dleq::new(group.clone(), vec![]).map(|_| ()),
+            Err(ZkpError::LengthMismatch("bases"))
+        );
+        assert_eq!(
+            Dleq::new(group, vec![zkp.alpha.clone(), zkp.group.identity()]).map(|_| ()),
+            Err(ZkpError::IdentityElement("g"))
+        );
+    }
+}
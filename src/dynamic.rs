@@ -0,0 +1,218 @@
+use rand_core::CryptoRngCore;
+
+use crate::group::Group;
+use crate::session::{AwaitingResponse, Commitment, Response, Verifier};
+use crate::{ChaumPedersen, Protocol, ZkpError};
+
+/// Byte-level view of a Chaum-Pedersen instance, for callers such as the
+/// server that only learn which group to use at runtime.
+pub trait DynChaumPedersen: Send + Sync {
+    // Check the bytes decode to an element of the subgroup of order q
+    fn check_encoded(&self, name: &'static str, bytes: &[u8]) -> Result<(), ZkpError>;
+
+    // Random challenge, encoded
+    fn random_challenge(&self) -> Vec<u8> {
+        self.random_challenge_with_rng(&mut rand::thread_rng())
+    }
+
+    fn random_challenge_with_rng(&self, rng: &mut dyn CryptoRngCore) -> Vec<u8>;
+
+    // Decode and check the public key and the commitment and draw the
+    // challenge, encoded, with the verifier waiting for the response to it
+    fn challenge_encoded<'a>(
+        &'a self,
+        y1: &[u8],
+        y2: &[u8],
+        r1: &[u8],
+        r2: &[u8],
+    ) -> Result<(Vec<u8>, Box<dyn DynAwaitingResponse + 'a>), ZkpError> {
+        self.challenge_encoded_with_rng(y1, y2, r1, r2, &mut rand::thread_rng())
+    }
+
+    fn challenge_encoded_with_rng<'a>(
+        &'a self,
+        y1: &[u8],
+        y2: &[u8],
+        r1: &[u8],
+        r2: &[u8],
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Vec<u8>, Box<dyn DynAwaitingResponse + 'a>), ZkpError>;
+
+    // Decode and check everything and run verify
+    fn verify_encoded(
+        &self,
+        y1: &[u8],
+        y2: &[u8],
+        r1: &[u8],
+        r2: &[u8],
+        s: &[u8],
+        c: &[u8],
+    ) -> Result<(), ZkpError>;
+}
+
+/// Byte-level view of an `AwaitingResponse`, from `challenge_encoded`.
+///
+/// Verifying consumes it, so a response is checked against its challenge at
+/// most once.
+pub trait DynAwaitingResponse: Send {
+    // Decode s and run verify
+    fn verify_encoded(self: Box<Self>, s: &[u8]) -> Result<(), ZkpError>;
+}
+
+// The verifier's state together with the instance that decodes s for it
+struct EncodedAwaitingResponse<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    state: AwaitingResponse<'a, G>,
+}
+
+impl<G> DynChaumPedersen for ChaumPedersen<G>
+where
+    G: Group + Send + Sync,
+    G::Element: Send + Sync,
+    G::Scalar: Send,
+{
+    fn check_encoded(&self, name: &'static str, bytes: &[u8]) -> Result<(), ZkpError> {
+        self.decode_element(name, bytes).map(|_| ())
+    }
+
+    fn random_challenge_with_rng(&self, mut rng: &mut dyn CryptoRngCore) -> Vec<u8> {
+        self.group
+            .scalar_to_bytes(&self.generate_random_with_rng(&mut rng))
+    }
+
+    fn challenge_encoded_with_rng<'a>(
+        &'a self,
+        y1: &[u8],
+        y2: &[u8],
+        r1: &[u8],
+        r2: &[u8],
+        mut rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Vec<u8>, Box<dyn DynAwaitingResponse + 'a>), ZkpError> {
+        let public_key = (
+            self.decode_element("y1", y1)?,
+            self.decode_element("y2", y2)?,
+        );
+        // Only decoded here, `challenge` checks them
+        let decode = |name, bytes| {
+            self.group
+                .element_from_bytes(bytes)
+                .ok_or(ZkpError::Decoding(name))
+        };
+        let commitment = Commitment {
+            r1: decode("r1", r1)?,
+            r2: decode("r2", r2)?,
+        };
+        let (c, state) =
+            Verifier::new(self, public_key).challenge_with_rng(commitment, &mut rng)?;
+        let next = EncodedAwaitingResponse { zkp: self, state };
+        Ok((self.group.scalar_to_bytes(&c), Box::new(next)))
+    }
+
+    fn verify_encoded(
+        &self,
+        y1: &[u8],
+        y2: &[u8],
+        r1: &[u8],
+        r2: &[u8],
+        s: &[u8],
+        c: &[u8],
+    ) -> Result<(), ZkpError> {
+        self.verify(
+            &self.decode_element("y1", y1)?,
+            &self.decode_element("y2", y2)?,
+            &self.decode_element("r1", r1)?,
+            &self.decode_element("r2", r2)?,
+            &self.decode_scalar("s", s)?,
+            &self.decode_scalar("c", c)?,
+        )
+    }
+}
+
+impl<G> DynAwaitingResponse for EncodedAwaitingResponse<'_, G>
+where
+    G: Group + Send + Sync,
+    G::Element: Send + Sync,
+    G::Scalar: Send,
+{
+    fn verify_encoded(self: Box<Self>, s: &[u8]) -> Result<(), ZkpError> {
+        let s = self.zkp.decode_scalar("s", s)?;
+        self.state.verify(&Response { s })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Sigma;
+    use crate::ParamSet;
+
+    // Run the protocol on a concrete instance and check it through the
+    // byte-level interface of the matching parameter set
+    fn check_dynamic<G: Group>(zkp: &ChaumPedersen<G>, set: ParamSet) {
+        let group = &zkp.group;
+        let dyn_zkp = set.protocol();
+
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+        let c = group
+            .scalar_from_bytes(&dyn_zkp.random_challenge())
+            .unwrap();
+
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let s = zkp.solve(&k, &c, &x);
+        let s_fake = zkp.solve(&k, &c, &zkp.generate_secret_key());
+
+        let y1 = group.element_to_bytes(&y1);
+        let y2 = group.element_to_bytes(&y2);
+        let r1 = group.element_to_bytes(&r1);
+        let r2 = group.element_to_bytes(&r2);
+        let c = group.scalar_to_bytes(&c);
+        assert_eq!(dyn_zkp.check_encoded("y1", &y1), Ok(()));
+        assert_eq!(
+            dyn_zkp.check_encoded("y1", &y1[1..]),
+            Err(ZkpError::Decoding("y1"))
+        );
+        assert_eq!(
+            dyn_zkp.verify_encoded(&y1, &y2, &r1, &r2, &group.scalar_to_bytes(&s), &c),
+            Ok(())
+        );
+        assert_eq!(
+            dyn_zkp.verify_encoded(&y1, &y2, &r1, &r2, &group.scalar_to_bytes(&s_fake), &c),
+            Err(ZkpError::VerificationFailed)
+        );
+        assert_eq!(
+            dyn_zkp.verify_encoded(&y1, &y2, &r1, &r2, &[0xff; 3], &c),
+            Err(ZkpError::Decoding("s"))
+        );
+
+        // The same through the verifier's state, which a response consumes
+        let (c, awaiting) = dyn_zkp.challenge_encoded(&y1, &y2, &r1, &r2).unwrap();
+        let s = zkp.solve(&k, &group.scalar_from_bytes(&c).unwrap(), &x);
+        assert_eq!(awaiting.verify_encoded(&group.scalar_to_bytes(&s)), Ok(()));
+        let (_, awaiting) = dyn_zkp.challenge_encoded(&y1, &y2, &r1, &r2).unwrap();
+        assert_eq!(
+            awaiting.verify_encoded(&group.scalar_to_bytes(&s_fake)),
+            Err(ZkpError::VerificationFailed)
+        );
+        assert_eq!(
+            dyn_zkp.challenge_encoded(&y1, &y2, &r1[1..], &r2).err(),
+            Some(ZkpError::Decoding("r1"))
+        );
+    }
+
+    #[test]
+    fn test_dynamic() {
+        let (alpha, beta, p, q, challenge_bound) = crate::ZKP::get_1024_bits_config().unwrap();
+        check_dynamic(
+            &crate::ZKP::new(alpha, beta, p, q, challenge_bound),
+            ParamSet::Rfc5114_1024_160,
+        );
+        #[cfg(feature = "ristretto")]
+        check_dynamic(&crate::RistrettoZKP::ristretto255(), ParamSet::Ristretto255);
+        #[cfg(feature = "p256")]
+        check_dynamic(&crate::P256ZKP::p256(), ParamSet::P256);
+        #[cfg(feature = "secp256k1")]
+        check_dynamic(&crate::Secp256k1ZKP::secp256k1(), ParamSet::Secp256k1);
+    }
+}
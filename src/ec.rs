@@ -0,0 +1,252 @@
+use std::marker::PhantomData;
+
+use elliptic_curve::bigint::Encoding;
+use elliptic_curve::generic_array::typenum::Unsigned;
+use elliptic_curve::generic_array::GenericArray;
+use elliptic_curve::group::cofactor::CofactorGroup;
+use elliptic_curve::hash2curve::{ExpandMsgXmd, FromOkm, GroupDigest};
+use elliptic_curve::sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use elliptic_curve::{
+    AffinePoint, CurveArithmetic, Field, FieldBytesSize, PrimeField, ProjectivePoint, Scalar,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use crate::group::Group;
+use crate::ChaumPedersen;
+
+/// Prime-order elliptic curve group from the RustCrypto `elliptic-curve` family.
+///
+/// Elements travel as SEC1 compressed points. Decoding rejects uncompressed
+/// encodings and anything that is not a point on the curve; both supported
+/// curves have cofactor 1, so being on the curve means being in the group.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EcGroup<C> {
+    curve: PhantomData<C>,
+}
+
+impl<C> Group for EcGroup<C>
+where
+    C: CurveArithmetic + GroupDigest,
+    ProjectivePoint<C>: CofactorGroup,
+    Scalar<C>: FromOkm,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    type Element = ProjectivePoint<C>;
+    type Scalar = Scalar<C>;
+
+    fn identity(&self) -> ProjectivePoint<C> {
+        <ProjectivePoint<C> as elliptic_curve::Group>::identity()
+    }
+
+    fn exp(&self, base: &ProjectivePoint<C>, exponent: &Scalar<C>) -> ProjectivePoint<C> {
+        *base * exponent
+    }
+
+    fn op(&self, a: &ProjectivePoint<C>, b: &ProjectivePoint<C>) -> ProjectivePoint<C> {
+        *a + b
+    }
+
+    fn scalar_add(&self, a: &Scalar<C>, b: &Scalar<C>) -> Scalar<C> {
+        *a + b
+    }
+
+    fn scalar_sub(&self, a: &Scalar<C>, b: &Scalar<C>) -> Scalar<C> {
+        *a - b
+    }
+
+    fn scalar_mul(&self, a: &Scalar<C>, b: &Scalar<C>) -> Scalar<C> {
+        *a * b
+    }
+
+    fn zeroize_scalar(scalar: &mut Scalar<C>) {
+        scalar.zeroize();
+    }
+
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar<C> {
+        Scalar::<C>::random(rng)
+    }
+
+    fn hash_to_scalar(&self, digest: &[u8]) -> Scalar<C> {
+        C::hash_to_scalar::<ExpandMsgXmd<Sha256>>(&[digest], &[b"zkp-chaum-pedersen-challenge"])
+            .expect("hash to scalar failed with a valid domain separation tag")
+    }
+
+    // Same reduction hash-to-scalar uses, 48 bytes for both supported curves
+    fn uniform_bytes_len(&self) -> usize {
+        <Scalar<C> as FromOkm>::Length::USIZE
+    }
+
+    fn scalar_from_uniform_bytes(&self, bytes: &[u8]) -> Scalar<C> {
+        Scalar::<C>::from_okm(GenericArray::from_slice(bytes))
+    }
+
+    // Curves are told apart by their order
+    fn params_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"sec1".to_vec();
+        bytes.extend_from_slice(C::ORDER.to_be_bytes().as_ref());
+        bytes
+    }
+
+    fn element_to_bytes(&self, element: &ProjectivePoint<C>) -> Vec<u8> {
+        let affine: AffinePoint<C> = (*element).into();
+        affine.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<ProjectivePoint<C>> {
+        let encoded = EncodedPoint::<C>::from_bytes(bytes).ok()?;
+        if !encoded.is_compressed() && !encoded.is_identity() {
+            return None;
+        }
+        // from_encoded_point checks the curve equation
+        let affine: Option<AffinePoint<C>> = AffinePoint::<C>::from_encoded_point(&encoded).into();
+        affine.map(ProjectivePoint::<C>::from)
+    }
+
+    fn scalar_to_bytes(&self, scalar: &Scalar<C>) -> Vec<u8> {
+        scalar.to_repr().to_vec()
+    }
+
+    fn scalar_from_bytes(&self, bytes: &[u8]) -> Option<Scalar<C>> {
+        let mut repr = <Scalar<C> as PrimeField>::Repr::default();
+        if bytes.len() != repr.as_ref().len() {
+            return None;
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Scalar::<C>::from_repr(repr).into()
+    }
+}
+
+impl<C> ChaumPedersen<EcGroup<C>>
+where
+    C: CurveArithmetic + GroupDigest,
+    ProjectivePoint<C>: CofactorGroup,
+    Scalar<C>: FromOkm,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    // alpha is the curve's standard generator, beta is hashed to the curve
+    // with the given domain separation tag so that log_alpha(beta) is unknown
+    fn with_hashed_beta(dst: &[u8]) -> Self {
+        let beta = C::hash_from_bytes::<ExpandMsgXmd<Sha256>>(&[b"beta"], &[dst])
+            .expect("hash to curve failed with a valid domain separation tag");
+        ChaumPedersen::from_group(
+            EcGroup::default(),
+            <ProjectivePoint<C> as elliptic_curve::Group>::generator(),
+            beta,
+        )
+    }
+}
+
+// ZKP over NIST P-256
+#[cfg(feature = "p256")]
+pub type P256ZKP = ChaumPedersen<EcGroup<p256::NistP256>>;
+
+#[cfg(feature = "p256")]
+impl P256ZKP {
+    pub fn p256() -> P256ZKP {
+        Self::with_hashed_beta(b"zkp-chaum-pedersen-P256_XMD:SHA-256_SSWU_RO_")
+    }
+}
+
+// ZKP over secp256k1
+#[cfg(feature = "secp256k1")]
+pub type Secp256k1ZKP = ChaumPedersen<EcGroup<k256::Secp256k1>>;
+
+#[cfg(feature = "secp256k1")]
+impl Secp256k1ZKP {
+    pub fn secp256k1() -> Secp256k1ZKP {
+        Self::with_hashed_beta(b"zkp-chaum-pedersen-secp256k1_XMD:SHA-256_SSWU_RO_")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Sigma;
+    use crate::{Protocol, ZkpError};
+
+    fn run_protocol<C>(zkp: &ChaumPedersen<EcGroup<C>>)
+    where
+        C: CurveArithmetic + GroupDigest,
+        ProjectivePoint<C>: CofactorGroup,
+        Scalar<C>: FromOkm,
+        AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+        FieldBytesSize<C>: ModulusSize,
+    {
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+
+        let c = zkp.generate_random();
+
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+
+        //  fake secret
+        let x_fake = zkp.generate_secret_key();
+        let s_fake = zkp.solve(&k, &c, &x_fake);
+
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    fn check_encoding<C>(zkp: &ChaumPedersen<EcGroup<C>>)
+    where
+        C: CurveArithmetic + GroupDigest,
+        ProjectivePoint<C>: CofactorGroup,
+        Scalar<C>: FromOkm,
+        AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+        FieldBytesSize<C>: ModulusSize,
+    {
+        let group = &zkp.group;
+        let x = zkp.generate_random();
+        let y1 = group.exp(&zkp.alpha, &x);
+
+        // SEC1 compressed: tag byte plus the 32-byte x coordinate
+        let y1_bytes = group.element_to_bytes(&y1);
+        assert_eq!(y1_bytes.len(), 33);
+        assert!(y1_bytes[0] == 0x02 || y1_bytes[0] == 0x03);
+        assert_eq!(group.element_from_bytes(&y1_bytes), Some(y1));
+
+        let x_bytes = group.scalar_to_bytes(&x);
+        assert_eq!(x_bytes.len(), 32);
+        assert_eq!(group.scalar_from_bytes(&x_bytes), Some(x));
+
+        // uncompressed encodings are refused
+        let affine: AffinePoint<C> = y1.into();
+        let uncompressed = affine.to_encoded_point(false);
+        assert_eq!(group.element_from_bytes(uncompressed.as_bytes()), None);
+
+        // x = 0xff..ff is above the field modulus, so it is not a point
+        let mut off_curve = vec![0x02];
+        off_curve.extend_from_slice(&[0xff; 32]);
+        assert_eq!(group.element_from_bytes(&off_curve), None);
+        assert_eq!(group.scalar_from_bytes(&[0xff; 32]), None);
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_p256() {
+        let zkp = P256ZKP::p256();
+        assert_ne!(zkp.alpha, zkp.beta);
+        run_protocol(&zkp);
+        check_encoding(&zkp);
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_secp256k1() {
+        let zkp = Secp256k1ZKP::secp256k1();
+        assert_ne!(zkp.alpha, zkp.beta);
+        run_protocol(&zkp);
+        check_encoding(&zkp);
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use crate::group::Group;
+use crate::ZkpError;
+
+/// An element received from the other party that passed the checks the
+/// verifier relies on: it is in the subgroup of order q, which for mod p
+/// groups also means in [1, p - 1], and it is not the identity.
+///
+/// The verifier API only takes this type, so an element outside the group
+/// or in a small subgroup cannot reach the verification equations.
+pub struct GroupElement<G: Group>(G::Element);
+
+impl<G: Group> GroupElement<G> {
+    // Check the element, naming it in the error
+    pub fn new(group: &G, name: &'static str, element: G::Element) -> Result<Self, ZkpError> {
+        if !group.in_subgroup(&element) {
+            return Err(ZkpError::NotInSubgroup(name));
+        }
+        if element == group.identity() {
+            return Err(ZkpError::IdentityElement(name));
+        }
+        Ok(GroupElement(element))
+    }
+
+    // Decode a canonical encoding, then check it
+    pub fn from_bytes(group: &G, name: &'static str, bytes: &[u8]) -> Result<Self, ZkpError> {
+        let element = group
+            .element_from_bytes(bytes)
+            .ok_or(ZkpError::Decoding(name))?;
+        GroupElement::new(group, name, element)
+    }
+
+    pub fn element(&self) -> &G::Element {
+        &self.0
+    }
+
+    pub fn into_element(self) -> G::Element {
+        self.0
+    }
+}
+
+impl<G: Group> Clone for GroupElement<G> {
+    fn clone(&self) -> Self {
+        GroupElement(self.0.clone())
+    }
+}
+
+impl<G: Group> PartialEq for GroupElement<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<G: Group> fmt::Debug for GroupElement<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GroupElement").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ParamSet;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_rejects_invalid_elements() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let group = &zkp.group;
+        let check = |element: BigUint| GroupElement::new(group, "y1", element).map(|_| ());
+
+        assert_eq!(
+            check(BigUint::from(0u32)),
+            Err(ZkpError::NotInSubgroup("y1"))
+        );
+        assert_eq!(
+            check(BigUint::from(1u32)),
+            Err(ZkpError::IdentityElement("y1"))
+        );
+        assert_eq!(check(group.p.clone()), Err(ZkpError::NotInSubgroup("y1")));
+        assert_eq!(check(&group.p + 4u32), Err(ZkpError::NotInSubgroup("y1")));
+        // order 2
+        assert_eq!(check(&group.p - 1u32), Err(ZkpError::NotInSubgroup("y1")));
+
+        assert_eq!(check(zkp.alpha.clone()), Ok(()));
+        let bytes = group.element_to_bytes(&zkp.alpha);
+        assert_eq!(
+            GroupElement::from_bytes(group, "r1", &bytes).map(|e| e.into_element()),
+            Ok(zkp.alpha)
+        );
+        assert_eq!(
+            GroupElement::from_bytes(group, "r1", &bytes[1..]).map(|_| ()),
+            Err(ZkpError::Decoding("r1"))
+        );
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_rejects_identity_ristretto() {
+        let group = crate::Ristretto255;
+        let identity = group.identity();
+        assert_eq!(
+            GroupElement::new(&group, "r2", identity).map(|_| ()),
+            Err(ZkpError::IdentityElement("r2"))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use crate::modp::ParamError;
+
+/// Why a library operation failed. Inputs are named by their protocol
+/// variable (`y1`, `r2`, `s`, ...) so callers can report which one was bad.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZkpError {
+    // the public parameters failed validation
+    InvalidParameters(ParamError),
+    // no parameter set has this id, or the operation does not support it
+    UnsupportedGroup(String),
+    // the bytes are not a canonical encoding of an element or scalar
+    Decoding(&'static str),
+    // the scalar is not reduced mod q, or belongs to a group with another q
+    ScalarOutOfRange(&'static str),
+    // the element is not in the prime-order subgroup
+    NotInSubgroup(&'static str),
+    // the element is the identity, which no honest party sends
+    IdentityElement(&'static str),
+    // the list does not have one entry per base
+    LengthMismatch(&'static str),
+    // every input is well formed, but the equations do not hold
+    VerificationFailed,
+}
+
+impl fmt::Display for ZkpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkpError::InvalidParameters(err) => write!(f, "invalid parameters: {}", err),
+            ZkpError::UnsupportedGroup(group) => write!(f, "group {} is not supported", group),
+            ZkpError::Decoding(name) => write!(f, "{} is not a canonical encoding", name),
+            ZkpError::ScalarOutOfRange(name) => write!(f, "{} is not reduced mod q", name),
+            ZkpError::NotInSubgroup(name) => {
+                write!(f, "{} is not in the subgroup of order q", name)
+            }
+            ZkpError::IdentityElement(name) => write!(f, "{} is the identity element", name),
+            ZkpError::LengthMismatch(name) => write!(f, "{} has the wrong number of entries", name),
+            ZkpError::VerificationFailed => write!(f, "proof does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for ZkpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZkpError::InvalidParameters(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParamError> for ZkpError {
+    fn from(err: ParamError) -> Self {
+        ZkpError::InvalidParameters(err)
+    }
+}
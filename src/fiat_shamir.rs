@@ -0,0 +1,292 @@
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::{ChaumPedersen, Commitment, GroupElement, NonceMode, Protocol, SecretKey, ZkpError};
+
+/// Running hash of everything a Fiat-Shamir challenge must depend on.
+///
+/// Every input is length-prefixed, so no two different sequences of inputs
+/// hash the same way.
+#[derive(Clone)]
+pub struct FiatShamir {
+    hasher: Sha512,
+}
+
+impl FiatShamir {
+    // Start a transcript for the given protocol label
+    pub fn new(label: &[u8]) -> FiatShamir {
+        let mut transcript = FiatShamir {
+            hasher: Sha512::new(),
+        };
+        transcript.append(label);
+        transcript
+    }
+
+    pub fn append(&mut self, bytes: &[u8]) {
+        self.hasher.update((bytes.len() as u64).to_be_bytes());
+        self.hasher.update(bytes);
+    }
+
+    pub fn append_element<G: Group>(&mut self, group: &G, element: &G::Element) {
+        self.append(&group.element_to_bytes(element));
+    }
+
+    // Finish the transcript and map it to a challenge in Z_q
+    pub fn challenge<G: Group>(self, group: &G) -> G::Scalar {
+        group.hash_to_scalar(&self.digest())
+    }
+
+    // Finish the transcript as raw bytes
+    pub fn digest(self) -> Vec<u8> {
+        self.hasher.finalize().to_vec()
+    }
+}
+
+/// Non-interactive Chaum-Pedersen proof: the commitments and the response.
+/// The verifier recomputes the challenge from them.
+pub struct Proof<G: Group> {
+    pub r1: G::Element,
+    pub r2: G::Element,
+    pub s: G::Scalar,
+}
+
+// Written by hand: deriving would require the group itself to implement
+// them, not just its elements and scalars. The same holds for every type
+// generic over the group in this crate
+impl<G: Group> Clone for Proof<G> {
+    fn clone(&self) -> Self {
+        Proof {
+            r1: self.r1.clone(),
+            r2: self.r2.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: Group> PartialEq for Proof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r1 == other.r1 && self.r2 == other.r2 && self.s == other.s
+    }
+}
+
+impl<G: Group> std::fmt::Debug for Proof<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Proof")
+            .field("r1", &self.r1)
+            .field("r2", &self.r2)
+            .field("s", &self.s)
+            .finish()
+    }
+}
+
+impl<G: Group> Proof<G> {
+    // Length-prefixed r1 || r2 || s, for storing or forwarding the proof
+    pub fn to_bytes(&self, group: &G) -> Vec<u8> {
+        encode_fields(&[
+            group.element_to_bytes(&self.r1),
+            group.element_to_bytes(&self.r2),
+            group.scalar_to_bytes(&self.s),
+        ])
+    }
+
+    pub fn from_bytes(group: &G, bytes: &[u8]) -> Result<Proof<G>, ZkpError> {
+        let fields = decode_fields(bytes, 3).ok_or(ZkpError::Decoding("proof"))?;
+        Ok(Proof {
+            r1: group
+                .element_from_bytes(fields[0])
+                .ok_or(ZkpError::Decoding("r1"))?,
+            r2: group
+                .element_from_bytes(fields[1])
+                .ok_or(ZkpError::Decoding("r2"))?,
+            s: group
+                .scalar_from_bytes(fields[2])
+                .ok_or(ZkpError::Decoding("s"))?,
+        })
+    }
+}
+
+// Each field prefixed with its length as a u32
+pub(crate) fn encode_fields(fields: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for field in fields {
+        bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(field);
+    }
+    bytes
+}
+
+// Exactly `count` fields written by `encode_fields`, with nothing after them
+pub(crate) fn decode_fields(bytes: &[u8], count: usize) -> Option<Vec<&[u8]>> {
+    let mut rest = bytes;
+    let mut fields = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (len, tail) = rest.split_first_chunk::<4>()?;
+        let len = u32::from_be_bytes(*len) as usize;
+        if tail.len() < len {
+            return None;
+        }
+        let (field, tail) = tail.split_at(len);
+        fields.push(field);
+        rest = tail;
+    }
+    rest.is_empty().then_some(fields)
+}
+
+impl<G: Group> ChaumPedersen<G> {
+    // c = H(group, alpha, beta, y1, y2, r1, r2, context)
+    pub fn challenge(
+        &self,
+        y1: &G::Element,
+        y2: &G::Element,
+        r1: &G::Element,
+        r2: &G::Element,
+        context: &[u8],
+    ) -> G::Scalar {
+        self.challenge_values(&[y1, y2], &[r1, r2], context)
+    }
+
+    // Prove knowledge of x with y1 = alpha^x, y2 = beta^x in one message.
+    // The context binds the proof to its purpose, e.g. a user name and session.
+    pub fn prove_non_interactive(&self, x: &SecretKey<G>, context: &[u8]) -> Proof<G> {
+        self.prove_non_interactive_with_rng(x, context, &mut rand::thread_rng())
+    }
+
+    pub fn prove_non_interactive_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> Proof<G> {
+        self.prove_non_interactive_with_mode(x, context, NonceMode::Random, rng)
+    }
+
+    // The challenge depends on the commitment, so every mode is safe here
+    pub fn prove_non_interactive_with_mode<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        mode: NonceMode,
+        rng: &mut R,
+    ) -> Proof<G> {
+        let (r, s) = self.prove_values(x, context, mode, rng);
+        let Commitment { r1, r2 } = Self::commitment_from(r);
+        Proof { r1, r2, s }
+    }
+
+    // The commitments in the proof are checked like every other element
+    // from the prover
+    pub fn verify_non_interactive(
+        &self,
+        y1: &GroupElement<G>,
+        y2: &GroupElement<G>,
+        proof: &Proof<G>,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        let commitment = Commitment {
+            r1: proof.r1.clone(),
+            r2: proof.r2.clone(),
+        };
+        self.verify_proof(&[y1, y2], commitment, &proof.s, context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ZKP;
+
+    fn check_non_interactive<G: Group>(zkp: &ChaumPedersen<G>) {
+        let x = zkp.generate_secret_key();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let [y1, y2] = [y1, y2].map(|y| zkp.check_element("y", y).unwrap());
+
+        let proof = zkp.prove_non_interactive(&x, b"alice");
+        assert_eq!(
+            zkp.verify_non_interactive(&y1, &y2, &proof, b"alice"),
+            Ok(())
+        );
+
+        // bound to the context
+        assert_eq!(
+            zkp.verify_non_interactive(&y1, &y2, &proof, b"bob"),
+            Err(ZkpError::VerificationFailed)
+        );
+
+        // bound to the statement
+        let (y1_other, y2_other) = zkp.exponentiate(&zkp.generate_secret_key());
+        let [y1_other, y2_other] = [y1_other, y2_other].map(|y| zkp.check_element("y", y).unwrap());
+        assert_eq!(
+            zkp.verify_non_interactive(&y1_other, &y2_other, &proof, b"alice"),
+            Err(ZkpError::VerificationFailed)
+        );
+
+        // survives serialization
+        let bytes = proof.to_bytes(&zkp.group);
+        let decoded = Proof::from_bytes(&zkp.group, &bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(
+            zkp.verify_non_interactive(&y1, &y2, &decoded, b"alice"),
+            Ok(())
+        );
+        assert_eq!(
+            Proof::<G>::from_bytes(&zkp.group, &bytes[..bytes.len() - 1]),
+            Err(ZkpError::Decoding("proof"))
+        );
+
+        // a commitment that is not a valid element
+        let forged = Proof {
+            r1: zkp.group.identity(),
+            ..proof
+        };
+        assert_eq!(
+            zkp.verify_non_interactive(&y1, &y2, &forged, b"alice"),
+            Err(ZkpError::IdentityElement("r1"))
+        );
+    }
+
+    #[test]
+    fn test_non_interactive_1024_bits() {
+        let (alpha, beta, p, q, challenge_bound) = ZKP::get_1024_bits_config().unwrap();
+        check_non_interactive(&ZKP::new(alpha, beta, p, q, challenge_bound));
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_non_interactive_ristretto() {
+        check_non_interactive(&crate::RistrettoZKP::ristretto255());
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_non_interactive_p256() {
+        check_non_interactive(&crate::P256ZKP::p256());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_non_interactive_secp256k1() {
+        check_non_interactive(&crate::Secp256k1ZKP::secp256k1());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_seeded_vector() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let zkp = crate::RistrettoZKP::ristretto255();
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let x = zkp.generate_secret_key_with_rng(&mut rng);
+        let proof = zkp.prove_non_interactive_with_rng(&x, b"alice", &mut rng);
+        // r1 is the one from the seeded session in `session`, which draws the
+        // nonce after the secret in the same way
+        let expected = concat!(
+            "00000020a6f2456958fa0b3644dc4a438cb00de0279e740e4c38854791ffea56a4afe346",
+            "000000207a7be7eb02ae166076cf56c60fb3986e94b29be1e53475d7ac1bed8a6bff917d",
+            "00000020a4c60496fae696160888bfe7cfedf1d0a5e5101bdfe2fc46648dd31e3304c807",
+        );
+        assert_eq!(hex::encode(proof.to_bytes(&zkp.group)), expected);
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt::Debug;
+
+use rand::{CryptoRng, RngCore};
+
+/// A cyclic group of prime order q in which the Chaum-Pedersen protocol runs.
+///
+/// Elements are combined multiplicatively (`op`) and raised to scalars in Z_q
+/// (`exp`). Scalars carry their own arithmetic mod q so the prover can compute
+/// `s = k - c * x mod q` without knowing how the group is represented.
+pub trait Group {
+    type Element: Clone + Debug + PartialEq;
+    type Scalar: Clone + Debug + PartialEq;
+
+    // Neutral element of the group
+    fn identity(&self) -> Self::Element;
+
+    // base^exponent
+    fn exp(&self, base: &Self::Element, exponent: &Self::Scalar) -> Self::Element;
+
+    // base^exponent for a secret exponent, in time that does not depend on
+    // it. The default is `exp`, for groups where that already holds
+    fn exp_secret(&self, base: &Self::Element, exponent: &Self::Scalar) -> Self::Element {
+        self.exp(base, exponent)
+    }
+
+    // Whether the element is in the subgroup of order q. The default is for
+    // element types that cannot hold anything else
+    fn in_subgroup(&self, _element: &Self::Element) -> bool {
+        true
+    }
+
+    // Group operation a * b
+    fn op(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    // Product of bases[i]^exponents[i], for public values only. The default
+    // multiplies single exponentiations together
+    fn multi_exp(&self, bases: &[Self::Element], exponents: &[Self::Scalar]) -> Self::Element {
+        bases
+            .iter()
+            .zip(exponents)
+            .fold(self.identity(), |acc, (base, exponent)| {
+                self.op(&acc, &self.exp(base, exponent))
+            })
+    }
+
+    // Whether the scalar is reduced mod q. The default is for scalar types
+    // that always are
+    fn scalar_in_range(&self, _scalar: &Self::Scalar) -> bool {
+        true
+    }
+
+    // a + b mod q
+    fn scalar_add(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+
+    // a - b mod q
+    fn scalar_sub(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+
+    // a * b mod q
+    fn scalar_mul(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+
+    // a - b * c mod q for secret a and c, in time that does not depend on
+    // them. The default is built from `scalar_sub` and `scalar_mul`
+    fn sub_mul_secret(&self, a: &Self::Scalar, b: &Self::Scalar, c: &Self::Scalar) -> Self::Scalar {
+        self.scalar_sub(a, &self.scalar_mul(b, c))
+    }
+
+    // Overwrite a secret scalar in place, called when a `SecretKey` or
+    // `Nonce` is dropped
+    fn zeroize_scalar(scalar: &mut Self::Scalar);
+
+    // Uniformly random scalar in Z_q, for secrets and nonces
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Scalar;
+
+    // Random challenge, also used for batch weights. The default is uniform
+    // in Z_q; groups may draw from a smaller challenge space
+    fn random_challenge<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Scalar {
+        self.random_scalar(rng)
+    }
+
+    // Map a hash digest to a scalar, used to derive Fiat-Shamir challenges
+    fn hash_to_scalar(&self, digest: &[u8]) -> Self::Scalar;
+
+    // Number of uniformly random bytes `scalar_from_uniform_bytes` expects
+    fn uniform_bytes_len(&self) -> usize;
+
+    // Reduce uniformly random bytes to a scalar that is close to uniform in
+    // Z_q, used to turn key derivation output into a secret
+    fn scalar_from_uniform_bytes(&self, bytes: &[u8]) -> Self::Scalar;
+
+    // Unambiguous description of the group, bound into Fiat-Shamir challenges
+    fn params_bytes(&self) -> Vec<u8>;
+
+    fn element_to_bytes(&self, element: &Self::Element) -> Vec<u8>;
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<Self::Element>;
+    fn scalar_to_bytes(&self, scalar: &Self::Scalar) -> Vec<u8>;
+    fn scalar_from_bytes(&self, bytes: &[u8]) -> Option<Self::Scalar>;
+}
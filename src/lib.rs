@@ -1,88 +1,190 @@
-use num_bigint::{BigUint, RandBigInt};
+pub mod and_proof;
+pub mod batch;
+pub mod composition;
+pub mod dleq;
+pub mod dynamic;
+#[cfg(any(feature = "p256", feature = "secp256k1"))]
+pub mod ec;
+pub mod element;
+pub mod error;
+pub mod fiat_shamir;
+pub mod group;
+pub mod modp;
+pub mod nonce;
+pub mod or_proof;
+pub mod params;
+pub mod password;
+pub mod protocol;
+#[cfg(feature = "ristretto")]
+pub mod ristretto;
+pub mod schnorr;
+pub mod secret;
+pub mod session;
+
+pub use and_proof::{
+    AndAwaitingChallenge, AndAwaitingResponse, AndProof, AndProver, AndResponse, AndVerifier,
+};
+pub use batch::{BatchError, Transcript};
+pub use dleq::{
+    Dleq, DleqAwaitingChallenge, DleqAwaitingResponse, DleqProof, DleqProver, DleqVerifier,
+};
+pub use dynamic::{DynAwaitingResponse, DynChaumPedersen};
+#[cfg(any(feature = "p256", feature = "secp256k1"))]
+pub use ec::EcGroup;
+#[cfg(feature = "secp256k1")]
+pub use ec::Secp256k1ZKP;
+#[cfg(feature = "p256")]
+pub use ec::P256ZKP;
+pub use element::GroupElement;
+pub use error::ZkpError;
+pub use fiat_shamir::Proof;
+pub use group::Group;
+pub use modp::{DomainParameters, FixedBaseTable, ModPGroup, ParamError, ValidationPolicy};
+pub use nonce::NonceMode;
+pub use or_proof::{
+    OrAwaitingChallenge, OrAwaitingResponse, OrProof, OrProver, OrResponse, OrVerifier,
+};
+pub use params::ParamSet;
+pub use password::{KdfError, PasswordKdf};
+pub use protocol::Protocol;
+#[cfg(feature = "ristretto")]
+pub use ristretto::{Ristretto255, RistrettoZKP};
+pub use schnorr::{
+    Schnorr, SchnorrAwaitingChallenge, SchnorrAwaitingResponse, SchnorrProof, SchnorrProver,
+    SchnorrVerifier,
+};
+pub use secret::{Nonce, SecretKey};
+pub use session::{
+    AwaitingChallenge, AwaitingResponse, Commitment, DeferredAwaitingChallenge, DeferredProver,
+    Prover, Response, Verifier,
+};
+
+use rand::{CryptoRng, RngCore};
+
+use protocol::Sigma;
+
+// Chaum-Pedersen protocol over any prime-order group
+#[derive(Debug, Clone)]
+pub struct ChaumPedersen<G: Group> {
+    pub group: G,
+    pub alpha: G::Element,
+    pub beta: G::Element,
+}
 
-#[derive(Debug, Clone, Default)]
+// ZKP over the multiplicative group of integers mod p
+pub type ZKP = ChaumPedersen<ModPGroup>;
 
-// ZKP struct for Chaum-Pedersen protocol
-pub struct ZKP {
-    pub alpha: BigUint,
-    pub beta: BigUint,
-    pub p: BigUint,
-    pub q: BigUint,
-    pub rng_upper_bound: BigUint,
-}
+impl<G: Group> ChaumPedersen<G> {
+    // Create a new instance from a group and two generators of it
+    pub fn from_group(group: G, alpha: G::Element, beta: G::Element) -> Self {
+        ChaumPedersen { group, alpha, beta }
+    }
 
-impl ZKP {
-    // Create a new ZKP instance with provided parameters
-    pub fn new(
-        alpha: BigUint,
-        beta: BigUint,
-        p: BigUint,
-        q: BigUint,
-        rng_upper_bound: BigUint,
-    ) -> ZKP {
-        ZKP {
-            alpha,
-            beta,
-            p,
-            q,
-            rng_upper_bound,
-        }
+    // Compute the public key (y1, y2) = (alpha^x, beta^x)
+    pub fn exponentiate(&self, x: &SecretKey<G>) -> (G::Element, G::Element) {
+        self.exp_secret_pair(x.expose())
     }
-    // Get default 1024-bit configuration values
-    pub fn get_1024_bits_config() -> (BigUint, BigUint, BigUint, BigUint, BigUint) {
-        // Configuration parameters    
-        let rng_upper_bound = BigUint::new(vec![u32::MAX; 4]);
-        let p  = BigUint::from_bytes_be(&hex::decode("B10B8F96A080E01DDE92DE5EAE5D54EC52C99FBCFB06A3C69A6A9DCA52D23B616073E28675A23D189838EF1E2EE652C013ECB4AEA906112324975C3CD49B83BFACCBDD7D90C4BD7098488E9C219A73724EFFD6FAE5644738FAA31A4FF55BCCC0A151AF5F0DC8B4BD45BF37DF365C1A65E68CFDA76D4DA708DF1FB2BC2E4A4371").expect("could not convert p from hex"));
-        let q = BigUint::from_bytes_be(
-            &hex::decode("F518AA8781A8DF278ABA4E7D64B7CB9D49462353")
-                .expect("could not convert q from hex"),
-        );
-        let alpha = BigUint::from_bytes_be(&hex::decode("A4D1CBD5C3FD34126765A442EFB99905F8104DD258AC507FD6406CFF14266D31266FEA1E5C41564B777E690F5504F213160217B4B01B886A5E91547F9E2749F4D7FBD7D3B9A92EE1909D0D2263F80A76A6A24C087A091F531DBF0A0169B6A28AD662A4D18E73AFA32D779D5918D08BC8858F4DCEF97C2A24855E6EEB22B3B2E5").expect("could not convert alpha from hex"));
-        let beta = alpha.modpow(&BigUint::from(1_469_131_869u32), &p);
-        (alpha, beta, p, q, rng_upper_bound)
+
+    // Compute the commitment (r1, r2) = (alpha^k, beta^k). Outside the crate
+    // nonces are only used through `Prover`, which uses each one once
+    pub(crate) fn commit(&self, k: &Nonce<G>) -> (G::Element, G::Element) {
+        self.exp_secret_pair(k.expose())
     }
 
-    // Solve s = k - c * x mod q
-    pub fn solve(&self, k: &BigUint, c: &BigUint, x: &BigUint) -> BigUint {
-        let cx = c * x;
-        if *k >= cx {
-            // use modpow (1, q) to do mod(q)
-            return (k - cx).modpow(&BigUint::from(1u32), &self.q);
-        }
-        self.q.clone() - (cx - k).modpow(&BigUint::from(1u32), &self.q)
+    // (alpha^e, beta^e) for the secret or the nonce, in constant time
+    fn exp_secret_pair(&self, e: &G::Scalar) -> (G::Element, G::Element) {
+        (
+            self.group.exp_secret(&self.alpha, e),
+            self.group.exp_secret(&self.beta, e),
+        )
     }
+
     /// verify that :
     ///     r1 = alpha^s * y1^c
     ///     r2 = beta^s * y2^c
+    /// Everything here is public, so it takes the variable-time fast path,
+    /// and each right-hand side is a single two-base multi-exponentiation.
+    ///
+    /// The response s must be reduced mod q. The elements are checked when
+    /// they are wrapped in `GroupElement`, see `check_element`.
     pub fn verify(
         &self,
-        y1: &BigUint,
-        y2: &BigUint,
-        r1: &BigUint,
-        r2: &BigUint,
-        s: &BigUint,
-        c: &BigUint,
-    ) -> bool {
-        let r1_verified = *r1
-            == (self.alpha.modpow(s, &self.p) * y1.modpow(c, &self.p))
-                .modpow(&BigUint::from(1u32), &self.p);
-        let r2_verified = *r2
-            == (self.beta.modpow(s, &self.p) * y2.modpow(c, &self.p))
-                .modpow(&BigUint::from(1u32), &self.p);
-
-        r1_verified && r2_verified
-    }
-    
-    // Generate a random number within the given bounds
-    pub fn generate_random(&self) -> BigUint {
-        let mut rng = rand::thread_rng();
-        rng.gen_biguint_below(&self.rng_upper_bound)
+        y1: &GroupElement<G>,
+        y2: &GroupElement<G>,
+        r1: &GroupElement<G>,
+        r2: &GroupElement<G>,
+        s: &G::Scalar,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        self.verify_values(&[y1, y2], &[r1, r2], s, c)
+    }
+
+    // Decode an element received from the other party and check it
+    pub fn decode_element(
+        &self,
+        name: &'static str,
+        bytes: &[u8],
+    ) -> Result<GroupElement<G>, ZkpError> {
+        GroupElement::from_bytes(&self.group, name, bytes)
+    }
+
+    pub fn decode_scalar(&self, name: &'static str, bytes: &[u8]) -> Result<G::Scalar, ZkpError> {
+        self.group
+            .scalar_from_bytes(bytes)
+            .ok_or(ZkpError::Decoding(name))
+    }
+
+    // Generate a fresh nonce, one per proof
+    pub(crate) fn generate_nonce<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Nonce<G> {
+        Nonce::random(&self.group, rng)
+    }
+}
+
+impl<G: Group> Protocol<G> for ChaumPedersen<G> {
+    const LABEL: &'static [u8] = b"zkp-chaum-pedersen";
+
+    type PublicKey<T> = (T, T);
+    type Commitment = Commitment<G>;
+
+    fn group(&self) -> &G {
+        &self.group
+    }
+
+    fn bases(&self) -> Vec<&G::Element> {
+        vec![&self.alpha, &self.beta]
+    }
+
+    fn public_key_from<T>(h: Vec<T>) -> (T, T) {
+        let Ok([y1, y2]) = <[T; 2]>::try_from(h) else {
+            panic!("Chaum-Pedersen has two bases");
+        };
+        (y1, y2)
+    }
+
+    fn public_key_values<T>((y1, y2): (T, T)) -> Vec<T> {
+        vec![y1, y2]
+    }
+
+    fn commitment_from(r: Vec<G::Element>) -> Commitment<G> {
+        let (r1, r2) = Self::public_key_from(r);
+        Commitment { r1, r2 }
+    }
+
+    fn check_commitment(
+        &self,
+        commitment: Commitment<G>,
+    ) -> Result<Vec<GroupElement<G>>, ZkpError> {
+        Ok(vec![
+            self.check_element("r1", commitment.r1)?,
+            self.check_element("r2", commitment.r2)?,
+        ])
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use num_bigint::BigUint;
     #[test]
     fn test_toy_example() {
         let alpha = BigUint::from(4u32);
@@ -97,26 +199,32 @@ mod test {
             BigUint::from(1u32),
         );
 
-        let x = BigUint::from(6u32);
-        let k = BigUint::from(7u32);
+        let scalar = |n: u32| zkp.group.scalar(BigUint::from(n));
+        let x = scalar(6);
+        let k = scalar(7);
 
-        let c = BigUint::from(4u32);
+        let c = scalar(4);
 
-        let (y1, y2) = (&alpha.modpow(&x, &p), &beta.modpow(&x, &p));
+        let (y1, y2) = (&alpha.modpow(&x.value(), &p), &beta.modpow(&x.value(), &p));
         assert_eq!(y1, &BigUint::from(2u32));
         assert_eq!(y2, &BigUint::from(3u32));
-        let (r1, r2) = (&alpha.modpow(&k, &p), &beta.modpow(&k, &p));
+        let (r1, r2) = (&alpha.modpow(&k.value(), &p), &beta.modpow(&k.value(), &p));
         assert_eq!(r1, &BigUint::from(8u32));
         assert_eq!(r2, &BigUint::from(4u32));
+        let (k, x) = (Nonce::new(k), SecretKey::new(x));
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e.clone()).unwrap());
         let s = zkp.solve(&k, &c, &x);
-        assert_eq!(s, BigUint::from(5u32));
-        assert!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c));
+        assert_eq!(s, scalar(5));
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
 
         //  fake secret
-        let x_fake = BigUint::from(7u32);
+        let x_fake = SecretKey::new(scalar(7));
         let s_fake = zkp.solve(&k, &c, &x_fake);
 
-        assert!(!zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c));
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
     }
 
     #[test]
@@ -128,30 +236,43 @@ mod test {
 
         let zkp = ZKP::new(alpha.clone(), beta.clone(), p.clone(), q.clone(), p.clone());
 
-        let x = BigUint::from(6u32);
-        let k = zkp.generate_random();
-
-        let c = zkp.generate_random();
+        let x = zkp.group.scalar(BigUint::from(6u32));
+        // a nonce of 0 commits to the identity, which the checks reject,
+        // and a challenge of 0 accepts any secret, so draw both again
+        let zero = zkp.group.scalar(BigUint::from(0u32));
+        let mut k = zkp.generate_random();
+        while k == zero {
+            k = zkp.generate_random();
+        }
+        let mut c = zkp.generate_random();
+        while c == zero {
+            c = zkp.generate_random();
+        }
 
-        let (y1, y2) = (&alpha.modpow(&x, &p), &beta.modpow(&x, &p));
+        let (y1, y2) = (&alpha.modpow(&x.value(), &p), &beta.modpow(&x.value(), &p));
         assert_eq!(y1, &BigUint::from(2u32));
         assert_eq!(y2, &BigUint::from(3u32));
-        let (r1, r2) = (&alpha.modpow(&k, &p), &beta.modpow(&k, &p));
+        let (r1, r2) = (&alpha.modpow(&k.value(), &p), &beta.modpow(&k.value(), &p));
+        let (k, x) = (Nonce::new(k), SecretKey::new(x));
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e.clone()).unwrap());
         let s = zkp.solve(&k, &c, &x);
 
-        assert!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c));
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
 
         //  fake secret
-        let x_fake = BigUint::from(7u32);
+        let x_fake = SecretKey::new(zkp.group.scalar(BigUint::from(7u32)));
         let s_fake = zkp.solve(&k, &c, &x_fake);
 
-        assert!(!zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c));
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
     }
 
     #[test]
     fn test_1024_bits_constants() {
         // 1024 bits
-        let rng_upper_bound = BigUint::new(vec![u32::MAX; 4]);
+        let challenge_bound = BigUint::new(vec![u32::MAX; 4]);
         let p  = BigUint::from_bytes_be(&hex::decode("B10B8F96A080E01DDE92DE5EAE5D54EC52C99FBCFB06A3C69A6A9DCA52D23B616073E28675A23D189838EF1E2EE652C013ECB4AEA906112324975C3CD49B83BFACCBDD7D90C4BD7098488E9C219A73724EFFD6FAE5644738FAA31A4FF55BCCC0A151AF5F0DC8B4BD45BF37DF365C1A65E68CFDA76D4DA708DF1FB2BC2E4A4371").expect("could not convert p from hex"));
         let q = BigUint::from_bytes_be(
             &hex::decode("F518AA8781A8DF278ABA4E7D64B7CB9D49462353")
@@ -159,37 +280,38 @@ mod test {
         );
         let alpha = BigUint::from_bytes_be(&hex::decode("A4D1CBD5C3FD34126765A442EFB99905F8104DD258AC507FD6406CFF14266D31266FEA1E5C41564B777E690F5504F213160217B4B01B886A5E91547F9E2749F4D7FBD7D3B9A92EE1909D0D2263F80A76A6A24C087A091F531DBF0A0169B6A28AD662A4D18E73AFA32D779D5918D08BC8858F4DCEF97C2A24855E6EEB22B3B2E5").expect("could not convert alpha from hex"));
 
-        let mut zkp = ZKP::default();
-        zkp.alpha = alpha.clone();
-        zkp.p = p.clone();
-        zkp.q = q.clone();
-        zkp.rng_upper_bound = rng_upper_bound;
+        let group = ModPGroup::new(p.clone(), q.clone(), challenge_bound);
 
         // beta can be alpha ^any number because of prime order sets properties
-        let beta = alpha.modpow(&BigUint::from(zkp.generate_random()), &p);
-        zkp.beta = beta.clone();
-        let x = zkp.generate_random();
-        let k = zkp.generate_random();
+        let beta = alpha.modpow(&group.random_scalar(&mut rand::thread_rng()).value(), &p);
+        let zkp = ZKP::from_group(group, alpha.clone(), beta.clone());
+        let x = zkp.group.random_scalar(&mut rand::thread_rng());
+        let k = zkp.group.random_scalar(&mut rand::thread_rng());
 
         let c = zkp.generate_random();
 
-        let (y1, y2) = (&alpha.modpow(&x, &p), &beta.modpow(&x, &p));
-        let (r1, r2) = (&alpha.modpow(&k, &p), &beta.modpow(&k, &p));
+        let (y1, y2) = (&alpha.modpow(&x.value(), &p), &beta.modpow(&x.value(), &p));
+        let (r1, r2) = (&alpha.modpow(&k.value(), &p), &beta.modpow(&k.value(), &p));
+        let (k, x) = (Nonce::new(k), SecretKey::new(x));
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e.clone()).unwrap());
         let s = zkp.solve(&k, &c, &x);
 
-        assert!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c));
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
 
         //  fake secret
-        let x_fake = BigUint::from(7u32);
+        let x_fake = SecretKey::new(zkp.group.scalar(BigUint::from(7u32)));
         let s_fake = zkp.solve(&k, &c, &x_fake);
 
-        assert!(!zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c));
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
     }
 
     #[test]
     fn test_2048_bits_constants() {
         // 2048  bits
-        let rng_upper_bound = BigUint::new(vec![u32::MAX; 8]);
+        let challenge_bound = BigUint::new(vec![u32::MAX; 8]);
         let p  = BigUint::from_bytes_be(&hex::decode("AD107E1E9123A9D0D660FAA79559C51FA20D64E5683B9FD1B54B1597B61D0A75E6FA141DF95A56DBAF9A3C407BA1DF15EB3D688A309C180E1DE6B85A1274A0A66D3F8152AD6AC2129037C9EDEFDA4DF8D91E8FEF55B7394B7AD5B7D0B6C12207C9F98D11ED34DBF6C6BA0B2C8BBC27BE6A00E0A0B9C49708B3BF8A317091883681286130BC8985DB1602E714415D9330278273C7DE31EFDC7310F7121FD5A07415987D9ADC0A486DCDF93ACC44328387315D75E198C641A480CD86A1B9E587E8BE60E69CC928B2B9C52172E413042E9B23F10B0E16E79763C9B53DCF4BA80A29E3FB73C16B8E75B97EF363E2FFA31F71CF9DE5384E71B81C0AC4DFFE0C10E64F").expect("could not convert p from hex"));
         let q = BigUint::from_bytes_be(
             &hex::decode("801C0D34C58D93FE997177101F80535A4738CEBCBF389A99B36371EB")
@@ -198,31 +320,73 @@ mod test {
 
         let alpha = BigUint::from_bytes_be(&hex::decode("AC4032EF4F2D9AE39DF30B5C8FFDAC506CDEBE7B89998CAF74866A08CFE4FFE3A6824A4E10B9A6F0DD921F01A70C4AFAAB739D7700C29F52C57DB17C620A8652BE5E9001A8D66AD7C17669101999024AF4D027275AC1348BB8A762D0521BC98AE247150422EA1ED409939D54DA7460CDB5F6C6B250717CBEF180EB34118E98D119529A45D6F834566E3025E316A330EFBB77A86F0C1AB15B051AE3D428C8F8ACB70A8137150B8EEB10E183EDD19963DDD9E263E4770589EF6AA21E7F5F2FF381B539CCE3409D13CD566AFBB48D6C019181E1BCFE94B30269EDFE72FE9B6AA4BD7B5A0F1C71CFFF4C19C418E1F6EC017981BC087F2A7065B384B890D3191F2BFA").expect("could not convert alpha from hex"));
 
-        let mut zkp = ZKP::default();
-        zkp.alpha = alpha.clone();
-        zkp.p = p.clone();
-        zkp.q = q.clone();
-        zkp.rng_upper_bound = rng_upper_bound;
+        let group = ModPGroup::new(p.clone(), q.clone(), challenge_bound);
 
         // beta can be alpha ^any number because of prime order sets properties
-        let beta = alpha.modpow(&BigUint::from(zkp.generate_random()), &p);
+        let beta = alpha.modpow(&group.random_scalar(&mut rand::thread_rng()).value(), &p);
+        let zkp = ZKP::from_group(group, alpha.clone(), beta.clone());
 
-        zkp.beta = beta.clone();
-        let x = zkp.generate_random();
-        let k = zkp.generate_random();
+        let x = zkp.group.random_scalar(&mut rand::thread_rng());
+        let k = zkp.group.random_scalar(&mut rand::thread_rng());
 
         let c = zkp.generate_random();
 
-        let (y1, y2) = (&alpha.modpow(&x, &p), &beta.modpow(&x, &p));
-        let (r1, r2) = (&alpha.modpow(&k, &p), &beta.modpow(&k, &p));
+        let (y1, y2) = (&alpha.modpow(&x.value(), &p), &beta.modpow(&x.value(), &p));
+        let (r1, r2) = (&alpha.modpow(&k.value(), &p), &beta.modpow(&k.value(), &p));
+        let (k, x) = (Nonce::new(k), SecretKey::new(x));
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e.clone()).unwrap());
         let s = zkp.solve(&k, &c, &x);
 
-        assert!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c));
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
 
         //  fake secret
-        let x_fake = BigUint::from(7u32);
+        let x_fake = SecretKey::new(zkp.group.scalar(BigUint::from(7u32)));
         let s_fake = zkp.solve(&k, &c, &x_fake);
 
-        assert!(!zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c));
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "p is not supported by the constant-time arithmetic")]
+    fn test_secret_path_has_no_fallback() {
+        // validation rejects the even p, and the secret is never raised with
+        // the variable-time modpow instead
+        let [alpha, beta, p, q] = [4u32, 9, 22, 11].map(BigUint::from);
+        let zkp = ZKP::new(alpha, beta, p, q.clone(), q);
+        zkp.exponentiate(&zkp.generate_secret_key());
+    }
+
+    #[test]
+    fn test_errors() {
+        let (p, q) = (BigUint::from(23u32), BigUint::from(11u32));
+        let zkp = ZKP::new(BigUint::from(4u32), BigUint::from(9u32), p, q.clone(), q);
+        let one = BigUint::from(1u32);
+
+        // 5 generates all of Z_23^*, not just the subgroup of order 11
+        let check = |element: u32| zkp.check_element("y1", BigUint::from(element)).map(|_| ());
+        assert_eq!(check(2), Ok(()));
+        assert_eq!(check(5), Err(ZkpError::NotInSubgroup("y1")));
+        assert_eq!(check(1), Err(ZkpError::IdentityElement("y1")));
+        assert_eq!(
+            zkp.decode_element("r1", &[5]).map(|_| ()),
+            Err(ZkpError::NotInSubgroup("r1"))
+        );
+        assert_eq!(
+            zkp.decode_element("r1", &[0, 2]).map(|_| ()),
+            Err(ZkpError::Decoding("r1"))
+        );
+        assert_eq!(zkp.decode_scalar("s", &[11]), Err(ZkpError::Decoding("s")));
+
+        // a scalar of another group
+        let c = zkp.group.scalar(one.clone());
+        let s = modp::Scalar::new(one, &BigUint::from(13u32));
+        let y = zkp.check_element("y", BigUint::from(2u32)).unwrap();
+        assert_eq!(
+            zkp.verify(&y, &y, &y, &y, &s, &c),
+            Err(ZkpError::ScalarOutOfRange("s"))
+        );
     }
 }
@@ -0,0 +1,321 @@
+pub mod ct;
+pub mod encoding;
+pub mod fixed_base;
+pub mod generator;
+pub mod montgomery;
+pub mod paramgen;
+pub mod prime;
+pub mod scalar;
+pub mod validate;
+
+use std::sync::Arc;
+
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::Zeroize;
+
+use crate::group::Group;
+use crate::params::ParamSet;
+use crate::{ZkpError, ZKP};
+
+pub use fixed_base::FixedBaseTable;
+pub use generator::{derive_generator, verify_generator};
+pub use paramgen::{DomainParameters, ParamGenError};
+pub use scalar::Scalar;
+pub use validate::{ParamError, ValidationPolicy};
+
+// Public seed beta is derived from in the standard parameter sets, see
+// `derive_generator`
+pub const BETA_SEED: &[u8] = b"zkp-chaum-pedersen beta";
+pub const BETA_INDEX: u8 = 1;
+
+/// Order-q subgroup of the multiplicative group of integers mod p.
+///
+/// Secrets and nonces only go through `ct`, which needs p and q odd and at
+/// most `ct::MAX_BITS` wide; `ZKP::validate` rejects any other group, and the
+/// secret operations panic on it rather than run in variable time.
+#[derive(Debug, Clone, Default)]
+pub struct ModPGroup {
+    pub p: BigUint,
+    pub q: BigUint,
+    // Challenges are drawn below this bound, then reduced mod q. Secrets and
+    // nonces are always uniform in Z_q
+    pub challenge_bound: BigUint,
+    // Bases that are raised through a precomputed table
+    fixed_bases: Vec<Arc<FixedBaseTable>>,
+}
+
+impl ModPGroup {
+    pub fn new(p: BigUint, q: BigUint, challenge_bound: BigUint) -> ModPGroup {
+        ModPGroup {
+            p,
+            q,
+            challenge_bound,
+            fixed_bases: Vec::new(),
+        }
+    }
+
+    // Use the table whenever its base is raised, replacing any table for
+    // the same base. Clones of the group share the tables
+    pub fn add_fixed_base(&mut self, table: FixedBaseTable) {
+        self.fixed_bases.retain(|t| t.base() != table.base());
+        self.fixed_bases.push(Arc::new(table));
+    }
+
+    // value mod q as a scalar of this group
+    pub fn scalar(&self, value: BigUint) -> Scalar {
+        Scalar::new(value, &self.q)
+    }
+
+    pub fn fixed_base(&self, base: &BigUint) -> Option<&FixedBaseTable> {
+        self.fixed_bases
+            .iter()
+            .map(Arc::as_ref)
+            .find(|table| table.base() == base)
+    }
+}
+
+impl Group for ModPGroup {
+    type Element = BigUint;
+    type Scalar = Scalar;
+
+    fn identity(&self) -> BigUint {
+        BigUint::from(1u32)
+    }
+
+    fn exp(&self, base: &BigUint, exponent: &Scalar) -> BigUint {
+        let exponent = exponent.value();
+        self.fixed_base(base)
+            .and_then(|table| table.exp(&exponent, &self.p))
+            .unwrap_or_else(|| base.modpow(&exponent, &self.p))
+    }
+
+    // Never falls back to `modpow`: panics for an even p or one wider than
+    // `ct::MAX_BITS`, which validation rejects, and for a scalar of a wider q
+    fn exp_secret(&self, base: &BigUint, exponent: &Scalar) -> BigUint {
+        self.fixed_base(base)
+            .and_then(|table| table.exp_secret(exponent, &self.p))
+            .or_else(|| ct::ct_modpow(base, exponent.limbs(), self.q.bits(), &self.p))
+            .expect("p is not supported by the constant-time arithmetic")
+    }
+
+    // In [1, p - 1] and of order dividing q
+    fn in_subgroup(&self, element: &BigUint) -> bool {
+        *element != BigUint::from(0u32)
+            && *element < self.p
+            && element.modpow(&self.q, &self.p) == BigUint::from(1u32)
+    }
+
+    fn op(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.p
+    }
+
+    // Bases with a fixed-base table are raised through it, the others
+    // together with `montgomery::multi_exp`. A single base left over is
+    // cheaper with plain `modpow`, which skips Straus' table setup
+    fn multi_exp(&self, bases: &[BigUint], exponents: &[Scalar]) -> BigUint {
+        let mut fixed = BigUint::from(1u32);
+        let mut rest = (Vec::new(), Vec::new());
+        for (base, exponent) in bases.iter().zip(exponents) {
+            let exponent = exponent.value();
+            match self
+                .fixed_base(base)
+                .and_then(|t| t.exp(&exponent, &self.p))
+            {
+                Some(power) => fixed = (fixed * power) % &self.p,
+                None => {
+                    rest.0.push(base);
+                    rest.1.push(exponent);
+                }
+            }
+        }
+        let separately = || {
+            rest.0
+                .iter()
+                .zip(&rest.1)
+                .fold(self.identity(), |acc, (base, exponent)| {
+                    self.op(&acc, &base.modpow(exponent, &self.p))
+                })
+        };
+        let rest = match rest.0.len() {
+            0 | 1 => separately(),
+            _ => {
+                let exponents: Vec<&BigUint> = rest.1.iter().collect();
+                montgomery::multi_exp(&rest.0, &exponents, &self.p).unwrap_or_else(separately)
+            }
+        };
+        self.op(&fixed, &rest)
+    }
+
+    // Always reduced, but it may belong to a group with another q
+    fn scalar_in_range(&self, scalar: &Scalar) -> bool {
+        *scalar.modulus() == self.q
+    }
+
+    fn scalar_add(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a + b
+    }
+
+    fn scalar_sub(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a - b
+    }
+
+    fn scalar_mul(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a * b
+    }
+
+    // Panics like `exp_secret` when q is not supported
+    fn sub_mul_secret(&self, a: &Scalar, b: &Scalar, c: &Scalar) -> Scalar {
+        ct::ct_sub_mul(a.limbs(), b.limbs(), c.limbs(), &self.q)
+            .map(|value| self.scalar(value))
+            .expect("q is not supported by the constant-time arithmetic")
+    }
+
+    fn zeroize_scalar(scalar: &mut Scalar) {
+        scalar.zeroize();
+    }
+
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar {
+        Scalar::random(&self.q, rng)
+    }
+
+    // Below challenge_bound, then reduced mod q in case the bound is larger
+    fn random_challenge<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar {
+        self.scalar(rng.gen_biguint_below(&self.challenge_bound))
+    }
+
+    // The digest is stretched to uniform_bytes_len bytes with SHA-512 in
+    // counter mode, so the bias of the reduction mod q is below 2^-128 for
+    // every q, including the ~2047-bit ones of the ffdhe groups
+    fn hash_to_scalar(&self, digest: &[u8]) -> Scalar {
+        let len = self.uniform_bytes_len();
+        let mut bytes = Vec::with_capacity(len.next_multiple_of(64));
+        let mut counter = 0u32;
+        while bytes.len() < len {
+            let block = Sha512::new()
+                .chain_update(counter.to_be_bytes())
+                .chain_update(digest)
+                .finalize();
+            bytes.extend_from_slice(&block);
+            counter += 1;
+        }
+        bytes.truncate(len);
+        self.scalar_from_uniform_bytes(&bytes)
+    }
+
+    // 128 bits more than q, so the bias of the reduction is below 2^-128
+    fn uniform_bytes_len(&self) -> usize {
+        (self.q.bits() as usize).div_ceil(8) + 16
+    }
+
+    fn scalar_from_uniform_bytes(&self, bytes: &[u8]) -> Scalar {
+        self.scalar(BigUint::from_bytes_be(bytes))
+    }
+
+    fn params_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"modp".to_vec();
+        for n in [&self.p, &self.q] {
+            let n = n.to_bytes_be();
+            bytes.extend_from_slice(&(n.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&n);
+        }
+        bytes
+    }
+
+    // Fixed length for p, see `encoding::encode`
+    fn element_to_bytes(&self, element: &BigUint) -> Vec<u8> {
+        encoding::encode(&(element % &self.p), &self.p)
+    }
+
+    // Accepts exactly the canonical encodings of 1 to p - 1
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<BigUint> {
+        encoding::decode(bytes, &self.p).filter(|element| *element != BigUint::from(0u32))
+    }
+
+    // Fixed length for q
+    fn scalar_to_bytes(&self, scalar: &Scalar) -> Vec<u8> {
+        scalar.to_bytes_be(encoding::encoded_len(&self.q))
+    }
+
+    fn scalar_from_bytes(&self, bytes: &[u8]) -> Option<Scalar> {
+        encoding::decode(bytes, &self.q).map(|value| self.scalar(value))
+    }
+}
+
+impl ZKP {
+    // Create a new ZKP instance with provided parameters
+    pub fn new(
+        alpha: BigUint,
+        beta: BigUint,
+        p: BigUint,
+        q: BigUint,
+        challenge_bound: BigUint,
+    ) -> ZKP {
+        ZKP::from_group(ModPGroup::new(p, q, challenge_bound), alpha, beta)
+    }
+
+    // Precompute fixed-base tables for alpha and beta, which every later
+    // exponentiation of them uses, on the prover's and the verifier's side
+    pub fn with_fixed_base_tables(mut self) -> ZKP {
+        for base in [&self.alpha, &self.beta] {
+            let table = FixedBaseTable::new(&self.group, base);
+            self.group.add_fixed_base(table);
+        }
+        self
+    }
+
+    // Tables of alpha and beta, length-prefixed, or None if they were not
+    // precomputed. Load them back with `with_fixed_base_tables_from_bytes`
+    pub fn fixed_base_tables_to_bytes(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        for base in [&self.alpha, &self.beta] {
+            let table = self.group.fixed_base(base)?.to_bytes();
+            bytes.extend_from_slice(&(table.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(&table);
+        }
+        Some(bytes)
+    }
+
+    // Load tables written by `fixed_base_tables_to_bytes`, so a server does
+    // not have to build them at startup. Fails if they do not decode or are
+    // not for alpha and beta. See `FixedBaseTable::from_bytes` on trust
+    pub fn with_fixed_base_tables_from_bytes(mut self, bytes: &[u8]) -> Result<ZKP, ZkpError> {
+        let malformed = ZkpError::Decoding("fixed-base tables");
+        let mut rest = bytes;
+        for base in [self.alpha.clone(), self.beta.clone()] {
+            let (len, tail) = rest.split_first_chunk::<8>().ok_or(malformed.clone())?;
+            let len = usize::try_from(u64::from_be_bytes(*len)).map_err(|_| malformed.clone())?;
+            if tail.len() < len {
+                return Err(malformed);
+            }
+            let (table, tail) = tail.split_at(len);
+            let table = FixedBaseTable::from_bytes(&self.group, table)
+                .filter(|table| *table.base() == base)
+                .ok_or(malformed.clone())?;
+            self.group.add_fixed_base(table);
+            rest = tail;
+        }
+        if rest.is_empty() {
+            Ok(self)
+        } else {
+            Err(malformed)
+        }
+    }
+
+    // Get default 1024-bit configuration values, see `ParamSet::Rfc5114_1024_160`
+    pub fn get_1024_bits_config() -> Result<(BigUint, BigUint, BigUint, BigUint, BigUint), ZkpError>
+    {
+        let set = ParamSet::Rfc5114_1024_160;
+        let zkp = set
+            .zkp()
+            .ok_or_else(|| ZkpError::UnsupportedGroup(set.id().to_string()))?;
+        let ModPGroup {
+            p,
+            q,
+            challenge_bound,
+            ..
+        } = zkp.group;
+        Ok((zkp.alpha, zkp.beta, p, q, challenge_bound))
+    }
+}
@@ -0,0 +1,214 @@
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::subtle::{ConditionallySelectable, ConstantTimeEq};
+use crypto_bigint::{Limb, Uint, U1024, U2048, U256, U3072, U4096, U512, U6144, U8192};
+use num_bigint::BigUint;
+use zeroize::Zeroize;
+
+use super::fixed_base::digit;
+
+/// Widest modulus the functions here handle, in bits.
+pub const MAX_BITS: u64 = 8192;
+
+// Run the generic function at the smallest supported width that holds `bits`,
+// or give None for anything wider than `MAX_BITS`
+macro_rules! with_width {
+    ($bits:expr, $f:ident($($arg:expr),*)) => {
+        match $bits {
+            0..=256 => Some($f::<{ U256::LIMBS }>($($arg),*)),
+            257..=512 => Some($f::<{ U512::LIMBS }>($($arg),*)),
+            513..=1024 => Some($f::<{ U1024::LIMBS }>($($arg),*)),
+            1025..=2048 => Some($f::<{ U2048::LIMBS }>($($arg),*)),
+            2049..=3072 => Some($f::<{ U3072::LIMBS }>($($arg),*)),
+            3073..=4096 => Some($f::<{ U4096::LIMBS }>($($arg),*)),
+            4097..=6144 => Some($f::<{ U6144::LIMBS }>($($arg),*)),
+            6145..=8192 => Some($f::<{ U8192::LIMBS }>($($arg),*)),
+            _ => None,
+        }
+    };
+}
+
+fn to_uint<const LIMBS: usize>(n: &BigUint) -> Uint<LIMBS> {
+    let mut bytes = n.to_bytes_le();
+    bytes.resize(LIMBS * Limb::BYTES, 0);
+    Uint::from_le_slice(&bytes)
+}
+
+// For secret operands, given as the little-endian limbs of a `Scalar`. The
+// bytes go through one buffer of the final size, wiped after the copy
+fn limbs_to_uint<const LIMBS: usize>(limbs: &[u64]) -> Uint<LIMBS> {
+    let mut bytes = vec![0u8; LIMBS * Limb::BYTES];
+    for (chunk, limb) in bytes.chunks_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    let n = Uint::from_le_slice(&bytes);
+    bytes.zeroize();
+    n
+}
+
+// Bits the limbs span, which is public where their value is not
+fn limbs_bits(limbs: &[u64]) -> u64 {
+    limbs.len() as u64 * 64
+}
+
+// Whether every bit from `bits` up is clear, looking at all of them
+fn fits(limbs: &[u64], bits: u64) -> bool {
+    let high = limbs.iter().enumerate().fold(0, |high, (i, limb)| {
+        let low = bits.saturating_sub(i as u64 * 64);
+        high | limb.checked_shr(low as u32).unwrap_or(0)
+    });
+    high == 0
+}
+
+fn to_biguint<const LIMBS: usize>(n: &Uint<LIMBS>) -> BigUint {
+    let bytes: Vec<u8> = n.as_words().iter().flat_map(|w| w.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+fn modpow<const LIMBS: usize>(
+    base: &BigUint,
+    exponent: &[u64],
+    exponent_bits: u64,
+    modulus: &BigUint,
+) -> BigUint {
+    let params = DynResidueParams::new(&to_uint::<LIMBS>(modulus));
+    let base = DynResidue::new(&to_uint(base), params);
+    // every one of the low exponent_bits bits is processed, whatever its value
+    let exponent = limbs_to_uint::<LIMBS>(exponent);
+    to_biguint(
+        &base
+            .pow_bounded_exp(&exponent, exponent_bits as usize)
+            .retrieve(),
+    )
+}
+
+fn sub_mul<const LIMBS: usize>(a: &[u64], b: &[u64], c: &[u64], modulus: &BigUint) -> BigUint {
+    let params = DynResidueParams::new(&to_uint::<LIMBS>(modulus));
+    let [a, b, c] = [a, b, c].map(|n| DynResidue::new(&limbs_to_uint(n), params));
+    to_biguint(&(a - b * c).retrieve())
+}
+
+fn fixed_base_pow<const LIMBS: usize>(
+    rows: &[Vec<BigUint>],
+    exponent: &[u64],
+    modulus: &BigUint,
+) -> BigUint {
+    let params = DynResidueParams::new(&to_uint::<LIMBS>(modulus));
+    let mut acc = DynResidue::one(params);
+    for (i, row) in rows.iter().enumerate() {
+        let digit = digit(exponent, i) as u64;
+        // touch every entry of the row, keeping the one for the digit, so
+        // neither the branches nor the memory accesses depend on it
+        let mut entry = Uint::<LIMBS>::ONE;
+        for (d, power) in row.iter().enumerate() {
+            let hit = (d as u64 + 1).ct_eq(&digit);
+            entry = Uint::conditional_select(&entry, &to_uint(power), hit);
+        }
+        acc *= DynResidue::new(&entry, params);
+    }
+    to_biguint(&acc.retrieve())
+}
+
+/// base^exponent mod modulus in time independent of the exponent's value.
+///
+/// The exponent is given as little-endian 64-bit limbs, as a `Scalar` holds
+/// it. Only the low `exponent_bits` bits are processed, so pass a public
+/// bound such as the bit length of q rather than the width of the modulus.
+/// The width is picked from the bit length of the base and the modulus and
+/// the limb count of the exponent, so only those and the bound leak. None if
+/// the modulus is even, the exponent does not fit in the bound or anything is
+/// wider than `MAX_BITS`.
+pub fn ct_modpow(
+    base: &BigUint,
+    exponent: &[u64],
+    exponent_bits: u64,
+    modulus: &BigUint,
+) -> Option<BigUint> {
+    if !modulus.bit(0) || !fits(exponent, exponent_bits) {
+        return None;
+    }
+    let bits = base.bits().max(modulus.bits()).max(limbs_bits(exponent));
+    with_width!(bits, modpow(base, exponent, exponent_bits, modulus))
+}
+
+/// a - b * c mod modulus in time independent of the operands' values, with
+/// the operands as limbs and the same width rules as `ct_modpow`.
+pub fn ct_sub_mul(a: &[u64], b: &[u64], c: &[u64], modulus: &BigUint) -> Option<BigUint> {
+    if !modulus.bit(0) {
+        return None;
+    }
+    let bits = [a, b, c].map(limbs_bits).into_iter().max();
+    with_width!(
+        bits.unwrap_or(0).max(modulus.bits()),
+        sub_mul(a, b, c, modulus)
+    )
+}
+
+/// base^exponent mod modulus from the rows of a `FixedBaseTable`, in time
+/// independent of the exponent's value. None if the modulus is even or wider
+/// than `MAX_BITS`.
+pub fn ct_fixed_base_pow(
+    rows: &[Vec<BigUint>],
+    exponent: &[u64],
+    modulus: &BigUint,
+) -> Option<BigUint> {
+    if !modulus.bit(0) {
+        return None;
+    }
+    with_width!(modulus.bits(), fixed_base_pow(rows, exponent, modulus))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_matches_variable_time() {
+        let mut rng = rand::thread_rng();
+        for bits in [64, 256, 300, 1024, 2048, 3000] {
+            let modulus = rng.gen_biguint(bits) | BigUint::from(1u32);
+            let base = rng.gen_biguint_below(&modulus);
+            let exponent = rng.gen_biguint(bits);
+            assert_eq!(
+                ct_modpow(&base, &exponent.to_u64_digits(), bits, &modulus),
+                Some(base.modpow(&exponent, &modulus))
+            );
+
+            let [a, b, c] = [(); 3].map(|_| rng.gen_biguint(bits));
+            let expected = ((&a % &modulus) + &modulus - (&b * &c) % &modulus) % &modulus;
+            let [a_limbs, b_limbs, c_limbs] = [&a, &b, &c].map(BigUint::to_u64_digits);
+            assert_eq!(
+                ct_sub_mul(&a_limbs, &b_limbs, &c_limbs, &modulus),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let p = BigUint::from(23u32);
+        let q = BigUint::from(11u32);
+        assert_eq!(
+            ct_modpow(&BigUint::from(4u32), &[0], q.bits(), &p),
+            Some(BigUint::from(1u32))
+        );
+        // the toy example from lib.rs: s = 7 - 4 * 6 mod 11 = 5
+        assert_eq!(ct_sub_mul(&[7], &[4], &[6], &q), Some(BigUint::from(5u32)));
+
+        assert_eq!(
+            ct_modpow(&BigUint::from(4u32), &[11], q.bits(), &BigUint::from(22u32)),
+            None
+        );
+
+        let huge = (BigUint::from(1u32) << 8192u32).to_u64_digits();
+        assert_eq!(ct_modpow(&BigUint::from(4u32), &huge, 8193, &p), None);
+        // the exponent must fit in the bound, whatever its limb count
+        assert_eq!(ct_modpow(&BigUint::from(4u32), &[11, 0], 3, &p), None);
+        assert_eq!(ct_modpow(&BigUint::from(4u32), &[0, 1], 64, &p), None);
+        assert_eq!(
+            ct_modpow(&BigUint::from(4u32), &[11, 0], 4, &p),
+            Some(BigUint::from(4u32).modpow(&q, &p))
+        );
+        assert_eq!(ct_sub_mul(&huge, &huge, &huge, &q), None);
+    }
+}
@@ -0,0 +1,100 @@
+use num_bigint::BigUint;
+
+// Bytes needed for any value below the bound
+pub fn encoded_len(bound: &BigUint) -> usize {
+    ((bound - 1u32).bits() as usize).div_ceil(8)
+}
+
+/// Canonical encoding of n below a public bound: big-endian, left-padded with
+/// zeros to the byte length of the bound.
+///
+/// Every value has exactly one encoding, so bytes on the wire, in Fiat-Shamir
+/// transcripts and in storage can be compared directly. Panics if n is not
+/// below the bound.
+pub fn encode(n: &BigUint, bound: &BigUint) -> Vec<u8> {
+    assert!(n < bound, "value does not fit its encoding");
+    let len = encoded_len(bound);
+    let mut encoded = vec![0u8; len];
+    // to_bytes_be gives [0] for zero, which has no significant bytes
+    if *n != BigUint::from(0u32) {
+        let bytes = n.to_bytes_be();
+        encoded[len - bytes.len()..].copy_from_slice(&bytes);
+    }
+    encoded
+}
+
+// Inverse of `encode`. None unless the input has exactly the encoded length
+// and the value is below the bound
+pub fn decode(bytes: &[u8], bound: &BigUint) -> Option<BigUint> {
+    if bytes.len() != encoded_len(bound) {
+        return None;
+    }
+    let n = BigUint::from_bytes_be(bytes);
+    (n < *bound).then_some(n)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::Group;
+    use crate::{ParamSet, Protocol};
+
+    #[test]
+    fn test_round_trip() {
+        let bound = BigUint::from(0x1_0001u32);
+        assert_eq!(encoded_len(&bound), 3);
+        assert_eq!(encode(&BigUint::from(0u32), &bound), vec![0, 0, 0]);
+        assert_eq!(encode(&BigUint::from(0x1_0000u32), &bound), vec![1, 0, 0]);
+        assert_eq!(encode(&BigUint::from(5u32), &bound), vec![0, 0, 5]);
+        assert_eq!(decode(&[0, 0, 5], &bound), Some(BigUint::from(5u32)));
+
+        // shorter, longer and out of range
+        assert_eq!(decode(&[5], &bound), None);
+        assert_eq!(decode(&[0, 0, 0, 5], &bound), None);
+        assert_eq!(decode(&[1, 0, 1], &bound), None);
+        // a power of two bound fills its bytes exactly
+        assert_eq!(encoded_len(&BigUint::from(256u32)), 1);
+    }
+
+    #[test]
+    fn test_group_encoding() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let group = &zkp.group;
+        let (y1, _) = zkp.exponentiate(&zkp.generate_secret_key());
+
+        let y1_bytes = group.element_to_bytes(&y1);
+        assert_eq!(y1_bytes.len(), 128);
+        assert_eq!(group.element_from_bytes(&y1_bytes), Some(y1));
+        let one = group.element_to_bytes(&BigUint::from(1u32));
+        assert_eq!(one.len(), 128);
+        assert_eq!(group.element_from_bytes(&one), Some(BigUint::from(1u32)));
+
+        let s = group.scalar_to_bytes(&group.scalar(BigUint::from(7u32)));
+        assert_eq!(s.len(), 20);
+        assert_eq!(
+            group.scalar_from_bytes(&s),
+            Some(group.scalar(BigUint::from(7u32)))
+        );
+        // scalars are reduced mod q when built
+        assert_eq!(group.scalar_to_bytes(&group.scalar(&group.q + 7u32)), s);
+
+        assert_eq!(group.element_from_bytes(&[0u8; 128]), None);
+        assert_eq!(
+            group.element_from_bytes(&encode_unchecked(&group.p, 128)),
+            None
+        );
+        assert_eq!(group.element_from_bytes(&y1_bytes[1..]), None);
+        assert_eq!(
+            group.scalar_from_bytes(&encode_unchecked(&group.q, 20)),
+            None
+        );
+        assert_eq!(group.scalar_from_bytes(&[7]), None);
+    }
+
+    fn encode_unchecked(n: &BigUint, len: usize) -> Vec<u8> {
+        let bytes = n.to_bytes_be();
+        let mut encoded = vec![0u8; len - bytes.len()];
+        encoded.extend_from_slice(&bytes);
+        encoded
+    }
+}
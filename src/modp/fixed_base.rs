@@ -0,0 +1,262 @@
+use std::fmt;
+
+use num_bigint::BigUint;
+
+use super::ct;
+use super::{ModPGroup, Scalar};
+
+// Exponent bits per digit, for the rows of a table and for the windows of
+// `montgomery::multi_exp`. Each row holds 2^w - 1 powers, so a wider window
+// trades memory for fewer multiplications
+pub(super) const WINDOW: u64 = 4;
+
+/// Precomputed powers of one base for windowed fixed-base exponentiation.
+///
+/// Row i holds `base^(d * 2^(w * i))` for every digit d in [1, 2^w - 1], so
+/// `base^e` is the product of one entry per w-bit digit of e, with no
+/// squarings. The rows cover every exponent up to the bit length of q, which
+/// is every `Scalar` of the group; wider exponents are not handled.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FixedBaseTable {
+    base: BigUint,
+    rows: Vec<Vec<BigUint>>,
+}
+
+impl FixedBaseTable {
+    pub fn new(group: &ModPGroup, base: &BigUint) -> FixedBaseTable {
+        let base = base % &group.p;
+        let bits = group.q.bits();
+        let digits = (1usize << WINDOW) - 1;
+
+        let mut rows = Vec::new();
+        let mut row_base = base.clone();
+        for _ in 0..bits.div_ceil(WINDOW) {
+            let mut row = vec![row_base.clone()];
+            for d in 1..digits {
+                row.push((&row[d - 1] * &row_base) % &group.p);
+            }
+            // base^(2^w * 2^(w * i)), the first entry of the next row
+            row_base = (&row[digits - 1] * &row_base) % &group.p;
+            rows.push(row);
+        }
+        FixedBaseTable { base, rows }
+    }
+
+    pub fn base(&self) -> &BigUint {
+        &self.base
+    }
+
+    // Exponent bits the table covers
+    pub fn bits(&self) -> u64 {
+        self.rows.len() as u64 * WINDOW
+    }
+
+    // base^exponent, or None if the exponent is wider than the table
+    pub fn exp(&self, exponent: &BigUint, p: &BigUint) -> Option<BigUint> {
+        if exponent.bits() > self.bits() {
+            return None;
+        }
+        let exponent = exponent.to_u64_digits();
+        let mut acc = BigUint::from(1u32);
+        for (i, row) in self.rows.iter().enumerate() {
+            let digit = digit(&exponent, i);
+            if digit != 0 {
+                acc = (&acc * &row[digit - 1]) % p;
+            }
+        }
+        Some(acc)
+    }
+
+    // base^exponent for a secret exponent, in time that does not depend on
+    // it. None if the exponent's q is wider than the table or p is not
+    // supported by `ct::ct_fixed_base_pow`
+    pub fn exp_secret(&self, exponent: &Scalar, p: &BigUint) -> Option<BigUint> {
+        if exponent.modulus().bits() > self.bits() {
+            return None;
+        }
+        ct::ct_fixed_base_pow(&self.rows, exponent.limbs(), p)
+    }
+
+    // window || row count || base || every entry, each entry length-prefixed
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let entries = std::iter::once(&self.base).chain(self.rows.iter().flatten());
+        bytes.extend_from_slice(&(WINDOW as u32).to_be_bytes());
+        bytes.extend_from_slice(&(self.rows.len() as u32).to_be_bytes());
+        for entry in entries {
+            let entry = entry.to_bytes_be();
+            bytes.extend_from_slice(&(entry.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&entry);
+        }
+        bytes
+    }
+
+    /// Load a table written by `to_bytes` for the same group.
+    ///
+    /// Only the shape and the range of the entries are checked: checking the
+    /// entries themselves costs as much as building the table, so only load
+    /// tables from a source as trusted as the parameters.
+    pub fn from_bytes(group: &ModPGroup, bytes: &[u8]) -> Option<FixedBaseTable> {
+        let (window, rest) = bytes.split_first_chunk::<4>()?;
+        let (rows, mut rest) = rest.split_first_chunk::<4>()?;
+        if u32::from_be_bytes(*window) as u64 != WINDOW {
+            return None;
+        }
+        let rows = u32::from_be_bytes(*rows) as usize;
+        let digits = (1usize << WINDOW) - 1;
+        let bits = group.q.bits();
+        if rows as u64 != bits.div_ceil(WINDOW) {
+            return None;
+        }
+
+        let mut entries = Vec::with_capacity(rows * digits + 1);
+        while !rest.is_empty() {
+            let (len, tail) = rest.split_first_chunk::<4>()?;
+            let len = u32::from_be_bytes(*len) as usize;
+            if tail.len() < len {
+                return None;
+            }
+            let (entry, tail) = tail.split_at(len);
+            let entry = BigUint::from_bytes_be(entry);
+            if entry >= group.p {
+                return None;
+            }
+            entries.push(entry);
+            rest = tail;
+        }
+        if entries.len() != rows * digits + 1 {
+            return None;
+        }
+
+        let base = entries.remove(0);
+        let rows: Vec<Vec<BigUint>> = entries.chunks(digits).map(<[_]>::to_vec).collect();
+        if rows.first().map(|row| &row[0]) != Some(&base) {
+            return None;
+        }
+        Some(FixedBaseTable { base, rows })
+    }
+}
+
+// Tables are large, so only their size is shown
+impl fmt::Debug for FixedBaseTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBaseTable")
+            .field("base", &self.base)
+            .field("bits", &self.bits())
+            .finish()
+    }
+}
+
+// The i-th w-bit digit of the little-endian limbs, least significant first.
+// The window divides 64, so a digit never spans two limbs
+pub(super) fn digit(exponent: &[u64], i: usize) -> usize {
+    const _: () = assert!(64 % WINDOW == 0);
+    let bit = i * WINDOW as usize;
+    let limb = exponent.get(bit / 64).copied().unwrap_or(0);
+    (limb >> (bit % 64)) as usize & ((1 << WINDOW) - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::Group;
+    use crate::protocol::Sigma;
+    use crate::{ParamSet, Protocol, ZkpError};
+
+    #[test]
+    fn test_matches_modpow() {
+        let group = ParamSet::Rfc5114_1024_160.zkp().unwrap().group;
+        let base = group.random_scalar(&mut rand::thread_rng()).value() + 2u32;
+        let table = FixedBaseTable::new(&group, &base);
+
+        let zero = BigUint::from(0u32);
+        let q_minus_one = &group.q - 1u32;
+        for exponent in [
+            zero,
+            q_minus_one,
+            group.random_scalar(&mut rand::thread_rng()).value(),
+        ] {
+            let expected = base.modpow(&exponent, &group.p);
+            assert_eq!(table.exp(&exponent, &group.p), Some(expected.clone()));
+            let secret = group.scalar(exponent);
+            assert_eq!(table.exp_secret(&secret, &group.p), Some(expected));
+        }
+
+        let too_wide = BigUint::from(1u32) << table.bits();
+        assert_eq!(table.exp(&too_wide, &group.p), None);
+        let wider_q = Scalar::new(BigUint::from(1u32), &too_wide);
+        assert_eq!(table.exp_secret(&wider_q, &group.p), None);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let table = FixedBaseTable::new(&zkp.group, &zkp.alpha);
+        let bytes = table.to_bytes();
+        assert_eq!(FixedBaseTable::from_bytes(&zkp.group, &bytes), Some(table));
+
+        assert_eq!(
+            FixedBaseTable::from_bytes(&zkp.group, &bytes[..bytes.len() - 1]),
+            None
+        );
+        let other = ParamSet::Rfc5114_2048_256.zkp().unwrap();
+        assert_eq!(FixedBaseTable::from_bytes(&other.group, &bytes), None);
+    }
+
+    #[test]
+    fn test_zkp_with_tables() {
+        let plain = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        assert_eq!(plain.fixed_base_tables_to_bytes(), None);
+        let zkp = plain.clone().with_fixed_base_tables();
+        assert!(zkp.group.fixed_base(&zkp.alpha).is_some());
+        assert!(zkp.group.fixed_base(&zkp.beta).is_some());
+
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+        let c = zkp.generate_random();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        assert_eq!((y1.clone(), y2.clone()), plain.exponentiate(&x));
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+        let s_fake = zkp.solve(&k, &c, &zkp.generate_secret_key());
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
+
+        let bytes = zkp.fixed_base_tables_to_bytes().unwrap();
+        let loaded = plain
+            .clone()
+            .with_fixed_base_tables_from_bytes(&bytes)
+            .unwrap();
+        assert_eq!(
+            loaded.group.fixed_base(&loaded.beta),
+            zkp.group.fixed_base(&zkp.beta)
+        );
+        assert_eq!(loaded.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+
+        // alpha's table where beta's is expected
+        let alpha_table = FixedBaseTable::new(&plain.group, &plain.alpha).to_bytes();
+        let mut swapped = Vec::new();
+        for _ in 0..2 {
+            swapped.extend_from_slice(&(alpha_table.len() as u64).to_be_bytes());
+            swapped.extend_from_slice(&alpha_table);
+        }
+        let malformed = Err(ZkpError::Decoding("fixed-base tables"));
+        assert_eq!(
+            plain
+                .clone()
+                .with_fixed_base_tables_from_bytes(&swapped)
+                .map(|_| ()),
+            malformed
+        );
+        assert_eq!(
+            plain
+                .with_fixed_base_tables_from_bytes(&bytes[1..])
+                .map(|_| ()),
+            malformed
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+// Give up after this many counters; each attempt fails with probability
+// about 1/q, so reaching it means the parameters are broken
+const MAX_COUNT: u16 = u16::MAX;
+
+/// Derive a generator of the order-q subgroup from a public seed, following
+/// the verifiable canonical generation of FIPS 186-4, appendix A.2.3:
+///
+/// ```text
+/// W = SHA-256(seed || "ggen" || index || count)
+/// g = W^((p - 1) / q) mod p
+/// ```
+///
+/// retrying with the next count while g < 2. Nobody, including whoever picked
+/// the seed, learns the discrete log of g to any other base, and different
+/// indices give independent generators from the same seed.
+pub fn derive_generator(p: &BigUint, q: &BigUint, seed: &[u8], index: u8) -> Option<BigUint> {
+    derive_generator_with_count(p, q, seed, index).map(|(g, _)| g)
+}
+
+/// Re-derive the generator for (seed, index) and check it is `g`.
+pub fn verify_generator(p: &BigUint, q: &BigUint, seed: &[u8], index: u8, g: &BigUint) -> bool {
+    derive_generator(p, q, seed, index).as_ref() == Some(g)
+}
+
+fn derive_generator_with_count(
+    p: &BigUint,
+    q: &BigUint,
+    seed: &[u8],
+    index: u8,
+) -> Option<(BigUint, u16)> {
+    let one = BigUint::from(1u32);
+    if *q <= one || *p <= one || (p - &one) % q != BigUint::from(0u32) {
+        return None;
+    }
+    let e = (p - &one) / q;
+
+    for count in 1..=MAX_COUNT {
+        let w = Sha256::new()
+            .chain_update(seed)
+            .chain_update(b"ggen")
+            .chain_update([index])
+            .chain_update(count.to_be_bytes())
+            .finalize();
+        let g = BigUint::from_bytes_be(&w).modpow(&e, p);
+        if g > one {
+            return Some((g, count));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ZKP;
+
+    #[test]
+    fn test_derived_generator_has_order_q() {
+        let (_, _, p, q, _) = ZKP::get_1024_bits_config().unwrap();
+        let g = derive_generator(&p, &q, b"seed", 1).unwrap();
+        assert!(g > BigUint::from(1u32));
+        assert_eq!(g.modpow(&q, &p), BigUint::from(1u32));
+        assert!(verify_generator(&p, &q, b"seed", 1, &g));
+
+        // another seed or index gives another generator
+        assert!(!verify_generator(&p, &q, b"seed", 2, &g));
+        assert!(!verify_generator(&p, &q, b"other seed", 1, &g));
+    }
+
+    #[test]
+    fn test_1024_bits_config_beta_is_verifiable() {
+        let (alpha, beta, p, q, _) = ZKP::get_1024_bits_config().unwrap();
+        assert!(verify_generator(
+            &p,
+            &q,
+            crate::modp::BETA_SEED,
+            crate::modp::BETA_INDEX,
+            &beta
+        ));
+        // the old beta = alpha^1469131869 does not come from the seed
+        let old_beta = alpha.modpow(&BigUint::from(1_469_131_869u32), &p);
+        assert!(!verify_generator(
+            &p,
+            &q,
+            crate::modp::BETA_SEED,
+            crate::modp::BETA_INDEX,
+            &old_beta
+        ));
+    }
+
+    #[test]
+    fn test_toy_group() {
+        let (p, q) = (BigUint::from(23u32), BigUint::from(11u32));
+        let (g, count) = derive_generator_with_count(&p, &q, b"seed", 1).unwrap();
+        assert!(count >= 1);
+        assert_eq!(g.modpow(&q, &p), BigUint::from(1u32));
+
+        // q must divide p - 1
+        assert!(derive_generator(&p, &BigUint::from(7u32), b"seed", 1).is_none());
+    }
+}
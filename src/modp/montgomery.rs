@@ -0,0 +1,168 @@
+use num_bigint::BigUint;
+
+use super::fixed_base::{digit, WINDOW};
+
+/// Montgomery multiplication mod an odd modulus on little-endian 64-bit limbs,
+/// for the verifier's public computations.
+///
+/// Values in Montgomery form are `x * 2^(64n) mod m` for a modulus of n limbs,
+/// always fully reduced. Nothing here runs in constant time.
+pub struct Montgomery {
+    modulus: BigUint,
+    m: Vec<u64>,
+    // -m^-1 mod 2^64
+    m_inv: u64,
+}
+
+impl Montgomery {
+    // None for an even modulus, which has no Montgomery form
+    pub fn new(m: &BigUint) -> Option<Montgomery> {
+        if !m.bit(0) {
+            return None;
+        }
+        let modulus = m.clone();
+        let m = m.to_u64_digits();
+        // Newton's iteration doubles the number of correct low bits each step
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+        Some(Montgomery {
+            modulus,
+            m,
+            m_inv: inv.wrapping_neg(),
+        })
+    }
+
+    pub fn to_montgomery(&self, x: &BigUint) -> Vec<u64> {
+        let shifted = x << (64 * self.m.len());
+        let mut limbs = (shifted % &self.modulus).to_u64_digits();
+        limbs.resize(self.m.len(), 0);
+        limbs
+    }
+
+    pub fn from_montgomery(&self, x: &[u64]) -> BigUint {
+        let mut one = vec![0u64; self.m.len()];
+        one[0] = 1;
+        let bytes: Vec<u8> = self
+            .mul(x, &one)
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    pub fn one(&self) -> Vec<u64> {
+        self.to_montgomery(&BigUint::from(1u32))
+    }
+
+    // x * y * 2^(-64n) mod m, interleaving the product and the reduction
+    pub fn mul(&self, x: &[u64], y: &[u64]) -> Vec<u64> {
+        let n = self.m.len();
+        let mut z = vec![0u64; 2 * n];
+        let mut carry = 0u64;
+        for (i, &yi) in y.iter().enumerate() {
+            let c1 = add_mul(&mut z[i..n + i], x, yi);
+            // adding k * m makes limb i zero, it is dropped by the final shift
+            let k = z[i].wrapping_mul(self.m_inv);
+            let c2 = add_mul(&mut z[i..n + i], &self.m, k);
+            let (sum, o1) = carry.overflowing_add(c1);
+            let (sum, o2) = sum.overflowing_add(c2);
+            z[n + i] = sum;
+            carry = (o1 || o2) as u64;
+        }
+
+        // z < 2m here, one subtraction is enough
+        let mut z = z.split_off(n);
+        if carry != 0 || !less_than(&z, &self.m) {
+            let mut borrow = false;
+            for (zi, mi) in z.iter_mut().zip(&self.m) {
+                let (d, b1) = zi.overflowing_sub(*mi);
+                let (d, b2) = d.overflowing_sub(borrow as u64);
+                *zi = d;
+                borrow = b1 || b2;
+            }
+        }
+        z
+    }
+}
+
+// z += x * y, returning the carry out of the top limb
+fn add_mul(z: &mut [u64], x: &[u64], y: u64) -> u64 {
+    let mut carry = 0u64;
+    for (zi, xi) in z.iter_mut().zip(x) {
+        let t = *xi as u128 * y as u128 + *zi as u128 + carry as u128;
+        *zi = t as u64;
+        carry = (t >> 64) as u64;
+    }
+    carry
+}
+
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+    for (ai, bi) in a.iter().rev().zip(b.iter().rev()) {
+        if ai != bi {
+            return ai < bi;
+        }
+    }
+    false
+}
+
+/// Product of bases[i]^exponents[i] mod m with Straus' method: one shared
+/// run of squarings, and per base one multiplication by a precomputed power
+/// for every w bits of exponent. None for an even modulus.
+pub fn multi_exp(bases: &[&BigUint], exponents: &[&BigUint], m: &BigUint) -> Option<BigUint> {
+    let mont = Montgomery::new(m)?;
+    let tables: Vec<Vec<Vec<u64>>> = bases
+        .iter()
+        .map(|base| {
+            let base = mont.to_montgomery(base);
+            let mut table = vec![mont.one(), base.clone()];
+            for i in 2..1 << WINDOW {
+                table.push(mont.mul(&table[i - 1], &base));
+            }
+            table
+        })
+        .collect();
+    let bits = exponents.iter().map(|e| e.bits()).max().unwrap_or(0);
+    let exponents: Vec<Vec<u64>> = exponents.iter().map(|e| e.to_u64_digits()).collect();
+
+    let mut acc = mont.one();
+    for window in (0..bits.div_ceil(WINDOW) as usize).rev() {
+        for _ in 0..WINDOW {
+            acc = mont.mul(&acc, &acc);
+        }
+        for (table, exponent) in tables.iter().zip(&exponents) {
+            let digit = digit(exponent, window);
+            if digit != 0 {
+                acc = mont.mul(&acc, &table[digit]);
+            }
+        }
+    }
+    Some(mont.from_montgomery(&acc))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_matches_modpow() {
+        let mut rng = rand::thread_rng();
+        for bits in [64, 65, 1024, 2048] {
+            let m = rng.gen_biguint(bits) | BigUint::from(1u32);
+            let bases = [rng.gen_biguint(bits + 8), rng.gen_biguint_below(&m)];
+            let exponents = [rng.gen_biguint(256), rng.gen_biguint(128)];
+            let expected =
+                (bases[0].modpow(&exponents[0], &m) * bases[1].modpow(&exponents[1], &m)) % &m;
+            assert_eq!(
+                multi_exp(&[&bases[0], &bases[1]], &[&exponents[0], &exponents[1]], &m),
+                Some(expected)
+            );
+        }
+
+        let m = BigUint::from(23u32);
+        assert_eq!(multi_exp(&[], &[], &m), Some(BigUint::from(1u32)));
+        assert_eq!(multi_exp(&[], &[], &BigUint::from(22u32)), None);
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+
+use num_bigint::BigUint;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use super::generator::derive_generator;
+use super::prime::is_probable_prime;
+use crate::ZKP;
+
+// Output length of the hash used to grow p and q, in bits
+const OUTLEN: usize = 256;
+
+// Generator indices for alpha and beta in `derive_generator`
+const ALPHA_INDEX: u8 = 1;
+const BETA_INDEX: u8 = 2;
+
+/// A freshly generated DSA-style domain together with the seed and counter
+/// it came from, so that anyone can check it was not cooked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainParameters {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub alpha: BigUint,
+    pub beta: BigUint,
+    pub seed: Vec<u8>,
+    pub counter: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamGenError {
+    // q can be at most as long as the hash output and p must be longer than q
+    UnsupportedSizes { p_bits: usize, q_bits: usize },
+    // the parameter file could not be parsed
+    Malformed(String),
+}
+
+impl fmt::Display for ParamGenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamGenError::UnsupportedSizes { p_bits, q_bits } => write!(
+                f,
+                "cannot generate a {}-bit p with a {}-bit q, q must be 2 to {} bits and shorter than p",
+                p_bits, q_bits, OUTLEN
+            ),
+            ParamGenError::Malformed(reason) => write!(f, "malformed parameter file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ParamGenError {}
+
+// Miller-Rabin rounds, following FIPS 186-4 table C.1 for the larger sizes
+fn prime_rounds(p_bits: usize) -> usize {
+    if p_bits >= 3072 {
+        64
+    } else if p_bits >= 2048 {
+        56
+    } else {
+        40
+    }
+}
+
+fn hash(bytes: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&Sha256::digest(bytes))
+}
+
+// Big-endian encoding of n mod 2^(8 * len), zero padded to len bytes
+fn to_fixed_bytes(n: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = n.to_bytes_be();
+    let bytes = &bytes[bytes.len().saturating_sub(len)..];
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend_from_slice(bytes);
+    out
+}
+
+// q = 2^(N-1) + U + 1 - (U mod 2) with U = H(seed) mod 2^(N-1), if prime
+fn q_from_seed(seed: &[u8], q_bits: usize, rounds: usize) -> Option<BigUint> {
+    let top = BigUint::from(1u32) << (q_bits - 1);
+    let u = hash(seed) % &top;
+    let q = &top + &u + 1u32 - (&u % 2u32);
+    is_probable_prime(&q, rounds).then_some(q)
+}
+
+// Candidate p for one counter value, per FIPS 186-4 A.1.1.2 steps 11.1-11.5
+fn p_candidate(
+    seed: &BigUint,
+    seed_len: usize,
+    offset: usize,
+    p_bits: usize,
+    q: &BigUint,
+) -> BigUint {
+    let n = p_bits.div_ceil(OUTLEN) - 1;
+    let b = p_bits - 1 - n * OUTLEN;
+    let modulus = BigUint::from(1u32) << (seed_len * 8);
+
+    let mut w = BigUint::from(0u32);
+    for j in 0..=n {
+        let input = (seed + offset + j) % &modulus;
+        let mut v = hash(&to_fixed_bytes(&input, seed_len));
+        if j == n {
+            v %= BigUint::from(1u32) << b;
+        }
+        w += v << (j * OUTLEN);
+    }
+    let x = w + (BigUint::from(1u32) << (p_bits - 1));
+    let c = &x % (q * 2u32);
+    x + 1u32 - c
+}
+
+// p for one counter value if it is long enough and prime, steps 11.1-11.8
+fn p_for_counter(
+    seed: &BigUint,
+    seed_len: usize,
+    counter: u32,
+    p_bits: usize,
+    q: &BigUint,
+    rounds: usize,
+) -> Option<BigUint> {
+    let n = p_bits.div_ceil(OUTLEN) - 1;
+    let offset = 1 + counter as usize * (n + 1);
+    let p = p_candidate(seed, seed_len, offset, p_bits, q);
+    let min_p = BigUint::from(1u32) << (p_bits - 1);
+    (p >= min_p && is_probable_prime(&p, rounds)).then_some(p)
+}
+
+// Counters run from 0 to 4L - 1 before a new seed is drawn
+fn counter_limit(p_bits: usize) -> u32 {
+    4 * p_bits as u32
+}
+
+fn check_sizes(p_bits: usize, q_bits: usize) -> Result<(), ParamGenError> {
+    if !(2..=OUTLEN).contains(&q_bits) || p_bits <= q_bits {
+        return Err(ParamGenError::UnsupportedSizes { p_bits, q_bits });
+    }
+    Ok(())
+}
+
+impl DomainParameters {
+    /// Generate p and q of the given bit lengths (for example 2048/256 or
+    /// 3072/256) following FIPS 186-4, appendix A.1.1.2, then derive alpha
+    /// and beta from the same seed with `derive_generator`.
+    pub fn generate(p_bits: usize, q_bits: usize) -> Result<DomainParameters, ParamGenError> {
+        DomainParameters::generate_with_rng(p_bits, q_bits, &mut rand::thread_rng())
+    }
+
+    // The seeds are drawn from rng. Everything else follows from the seed,
+    // so the same rng state gives the same parameters
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(
+        p_bits: usize,
+        q_bits: usize,
+        rng: &mut R,
+    ) -> Result<DomainParameters, ParamGenError> {
+        check_sizes(p_bits, q_bits)?;
+        let rounds = prime_rounds(p_bits);
+
+        loop {
+            let mut seed = vec![0u8; q_bits.div_ceil(8)];
+            rng.fill_bytes(&mut seed);
+            let q = match q_from_seed(&seed, q_bits, rounds) {
+                Some(q) => q,
+                None => continue,
+            };
+
+            let seed_int = BigUint::from_bytes_be(&seed);
+            for counter in 0..counter_limit(p_bits) {
+                if let Some(p) = p_for_counter(&seed_int, seed.len(), counter, p_bits, &q, rounds) {
+                    return Ok(DomainParameters::from_primes(p, q, seed, counter));
+                }
+            }
+        }
+    }
+
+    fn from_primes(p: BigUint, q: BigUint, seed: Vec<u8>, counter: u32) -> DomainParameters {
+        let alpha =
+            derive_generator(&p, &q, &seed, ALPHA_INDEX).expect("q divides p - 1 by construction");
+        let beta =
+            derive_generator(&p, &q, &seed, BETA_INDEX).expect("q divides p - 1 by construction");
+        DomainParameters {
+            p,
+            q,
+            alpha,
+            beta,
+            seed,
+            counter,
+        }
+    }
+
+    /// Re-run the generation from the recorded seed and counter and check it
+    /// lands on exactly these parameters, as in FIPS 186-4 A.1.1.3: the
+    /// counter must be below 4L and the first one that gave a prime p.
+    pub fn verify(&self) -> bool {
+        let p_bits = self.p.bits() as usize;
+        let q_bits = self.q.bits() as usize;
+        if check_sizes(p_bits, q_bits).is_err()
+            || self.seed.len() < q_bits.div_ceil(8)
+            || self.counter >= counter_limit(p_bits)
+        {
+            return false;
+        }
+        let rounds = prime_rounds(p_bits);
+        let q = match q_from_seed(&self.seed, q_bits, rounds) {
+            Some(q) => q,
+            None => return false,
+        };
+        if q != self.q {
+            return false;
+        }
+        let seed = BigUint::from_bytes_be(&self.seed);
+        let p_for = |counter| p_for_counter(&seed, self.seed.len(), counter, p_bits, &q, rounds);
+        if p_for(self.counter).as_ref() != Some(&self.p)
+            || (0..self.counter).any(|counter| p_for(counter).is_some())
+        {
+            return false;
+        }
+        derive_generator(&self.p, &q, &self.seed, ALPHA_INDEX).as_ref() == Some(&self.alpha)
+            && derive_generator(&self.p, &q, &self.seed, BETA_INDEX).as_ref() == Some(&self.beta)
+    }
+
+    // ZKP instance over these parameters, challenges drawn below q
+    pub fn zkp(&self) -> ZKP {
+        ZKP::new(
+            self.alpha.clone(),
+            self.beta.clone(),
+            self.p.clone(),
+            self.q.clone(),
+            self.q.clone(),
+        )
+    }
+
+    /// Parameter file contents: one `name=value` line per field, numbers and
+    /// seed in hex, counter in decimal.
+    pub fn to_text(&self) -> String {
+        format!(
+            "p={}\nq={}\nalpha={}\nbeta={}\nseed={}\ncounter={}\n",
+            self.p.to_str_radix(16),
+            self.q.to_str_radix(16),
+            self.alpha.to_str_radix(16),
+            self.beta.to_str_radix(16),
+            hex::encode(&self.seed),
+            self.counter
+        )
+    }
+
+    pub fn from_text(text: &str) -> Result<DomainParameters, ParamGenError> {
+        let field = |name: &str| -> Result<&str, ParamGenError> {
+            text.lines()
+                .find_map(|line| line.trim().strip_prefix(name)?.strip_prefix('='))
+                .ok_or_else(|| ParamGenError::Malformed(format!("missing {}", name)))
+        };
+        let number = |name: &str| -> Result<BigUint, ParamGenError> {
+            BigUint::parse_bytes(field(name)?.as_bytes(), 16)
+                .ok_or_else(|| ParamGenError::Malformed(format!("{} is not hex", name)))
+        };
+        Ok(DomainParameters {
+            p: number("p")?,
+            q: number("q")?,
+            alpha: number("alpha")?,
+            beta: number("beta")?,
+            seed: hex::decode(field("seed")?)
+                .map_err(|_| ParamGenError::Malformed("seed is not hex".to_string()))?,
+            counter: field("counter")?
+                .parse()
+                .map_err(|_| ParamGenError::Malformed("counter is not a number".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::modp::ValidationPolicy;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_generate_and_verify() {
+        // small sizes keep the test fast, the code path is the same
+        let params = DomainParameters::generate(512, 160).unwrap();
+        assert_eq!(params.p.bits(), 512);
+        assert_eq!(params.q.bits(), 160);
+        assert!(params.verify());
+
+        let policy = ValidationPolicy {
+            min_p_bits: 512,
+            min_q_bits: 160,
+            prime_rounds: 40,
+        };
+        assert_eq!(params.zkp().validate_with(&policy), Ok(()));
+
+        // tampering with anything breaks verification
+        let mut tampered = params.clone();
+        tampered.counter += 1;
+        assert!(!tampered.verify());
+        let mut tampered = params.clone();
+        tampered.beta = params.alpha.modpow(&BigUint::from(2u32), &params.p);
+        assert!(!tampered.verify());
+        let mut tampered = params.clone();
+        tampered.seed[0] ^= 1;
+        assert!(!tampered.verify());
+    }
+
+    // The same seed with a later counter that also gives a prime p
+    fn later_prime(params: &DomainParameters, from: u32) -> DomainParameters {
+        let p_bits = params.p.bits() as usize;
+        let seed = BigUint::from_bytes_be(&params.seed);
+        let rounds = prime_rounds(p_bits);
+        (from..)
+            .find_map(|counter| {
+                let p = p_for_counter(&seed, params.seed.len(), counter, p_bits, &params.q, rounds);
+                p.map(|p| {
+                    DomainParameters::from_primes(p, params.q.clone(), params.seed.clone(), counter)
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rejects_skipped_counters() {
+        let mut rng = ChaCha20Rng::from_seed([3; 32]);
+        let params = DomainParameters::generate_with_rng(512, 160, &mut rng).unwrap();
+        assert!(params.verify());
+
+        // a prime p, but an earlier counter already gave one
+        let skipped = later_prime(&params, params.counter + 1);
+        assert!(skipped.counter < counter_limit(512));
+        assert!(!skipped.verify());
+
+        // a prime p past the last counter generation would try
+        let beyond = later_prime(&params, counter_limit(512));
+        assert!(!beyond.verify());
+    }
+
+    #[test]
+    fn test_seeded_generation() {
+        let generate = |seed| {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            DomainParameters::generate_with_rng(256, 128, &mut rng).unwrap()
+        };
+        assert_eq!(generate([7; 32]), generate([7; 32]));
+        assert_ne!(generate([7; 32]).seed, generate([8; 32]).seed);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let params = DomainParameters::generate(256, 128).unwrap();
+        let parsed = DomainParameters::from_text(&params.to_text()).unwrap();
+        assert_eq!(parsed, params);
+        assert!(parsed.verify());
+
+        assert!(DomainParameters::from_text("p=zz\n").is_err());
+    }
+
+    #[test]
+    fn test_unsupported_sizes() {
+        assert_eq!(
+            DomainParameters::generate(2048, 512),
+            Err(ParamGenError::UnsupportedSizes {
+                p_bits: 2048,
+                q_bits: 512
+            })
+        );
+        assert!(DomainParameters::generate(160, 160).is_err());
+    }
+}
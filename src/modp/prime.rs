@@ -0,0 +1,79 @@
+use num_bigint::{BigUint, RandBigInt};
+
+// Small primes used to rule out most composites before Miller-Rabin
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Miller-Rabin probable-prime test with `rounds` random bases.
+///
+/// A composite passes with probability at most 4^-rounds.
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    if *n < two {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        let p = BigUint::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p) == BigUint::from(0u32) {
+            return false;
+        }
+    }
+
+    // n - 1 = d * 2^r with d odd
+    let n_minus_one = n - &one;
+    let r = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> r;
+
+    let mut rng = rand::thread_rng();
+    'witness: for _ in 0..rounds {
+        let a = rng.gen_biguint_range(&two, &n_minus_one);
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..r {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_small_numbers() {
+        let primes: Vec<u32> = (0..200u32)
+            .filter(|n| *n >= 2 && (2..*n).all(|d| n % d != 0))
+            .collect();
+        for n in 0..200u32 {
+            assert_eq!(
+                is_probable_prime(&BigUint::from(n), 20),
+                primes.contains(&n),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        // 2^127 - 1 is a Mersenne prime, 2^128 + 1 is not prime
+        let m127 = (BigUint::from(1u32) << 127) - 1u32;
+        assert!(is_probable_prime(&m127, 20));
+        let f7 = (BigUint::from(1u32) << 128) + 1u32;
+        assert!(!is_probable_prime(&f7, 20));
+        // Carmichael number 561 = 3 * 11 * 17
+        assert!(!is_probable_prime(&BigUint::from(561u32), 20));
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::BigUint;
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
+
+/// An element of Z_q for the mod p groups.
+///
+/// The value is always reduced mod q and carries q with it, so arithmetic
+/// needs no group at hand. Combining scalars of different q panics.
+///
+/// The value is held as little-endian 64-bit limbs, exactly as many as q
+/// needs, in a buffer that is allocated at that size and never resized.
+/// `zeroize` therefore wipes every byte the value was ever stored in, which
+/// a `BigUint` cannot promise: it shrinks and reallocates its digits freely.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Scalar {
+    limbs: Vec<u64>,
+    q: BigUint,
+}
+
+// Limbs for any value below q
+fn limb_count(q: &BigUint) -> usize {
+    q.bits().div_ceil(64) as usize
+}
+
+impl Scalar {
+    // value mod q
+    pub fn new(value: BigUint, q: &BigUint) -> Scalar {
+        Scalar::reduced(&(value % q), q)
+    }
+
+    // None unless the value is already reduced, for values off the wire
+    pub fn from_reduced(value: BigUint, q: &BigUint) -> Option<Scalar> {
+        (value < *q).then(|| Scalar::reduced(&value, q))
+    }
+
+    fn reduced(value: &BigUint, q: &BigUint) -> Scalar {
+        let mut limbs = vec![0u64; limb_count(q)];
+        for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+            *limb = digit;
+        }
+        Scalar {
+            limbs,
+            q: q.clone(),
+        }
+    }
+
+    // Uniform in [0, q), drawn straight into the limbs. Draws of q's bit
+    // length are rejected until one is below q, consuming the rng exactly
+    // as `RandBigInt::gen_biguint_below` does
+    pub fn random<R: RngCore + CryptoRng>(q: &BigUint, rng: &mut R) -> Scalar {
+        let bits = q.bits();
+        let mut words = vec![0u32; bits.div_ceil(32) as usize];
+        let mut scalar = Scalar {
+            limbs: vec![0u64; limb_count(q)],
+            q: q.clone(),
+        };
+        loop {
+            rng.fill(&mut words[..]);
+            if !bits.is_multiple_of(32) {
+                let last = words.len() - 1;
+                words[last] >>= 32 - bits % 32;
+            }
+            for (limb, pair) in scalar.limbs.iter_mut().zip(words.chunks(2)) {
+                let high = pair.get(1).copied().unwrap_or(0);
+                *limb = u64::from(pair[0]) | u64::from(high) << 32;
+            }
+            if scalar.below_q() {
+                words.zeroize();
+                return scalar;
+            }
+        }
+    }
+
+    // Compared limb by limb from the top, without a BigUint copy
+    fn below_q(&self) -> bool {
+        let q: Vec<u64> = self.q.iter_u64_digits().collect();
+        for (limb, q) in self.limbs.iter().zip(&q).rev() {
+            if limb != q {
+                return limb < q;
+            }
+        }
+        false
+    }
+
+    // A copy of the value. The copy is not wiped, so only take it for
+    // public scalars; secret ones are used through `limbs`
+    pub fn value(&self) -> BigUint {
+        let digits: Vec<u32> = self
+            .limbs
+            .iter()
+            .flat_map(|&limb| [limb as u32, (limb >> 32) as u32])
+            .collect();
+        BigUint::new(digits)
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.q
+    }
+
+    pub(super) fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    // Big-endian, left-padded with zeros to len bytes, see
+    // `encoding::encode`. Panics if the value does not fit
+    pub(super) fn to_bytes_be(&self, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; len];
+        let mut overflow = 0u8;
+        let le = self.limbs.iter().flat_map(|limb| limb.to_le_bytes());
+        for (i, byte) in le.enumerate() {
+            match len.checked_sub(i + 1) {
+                Some(at) => bytes[at] = byte,
+                None => overflow |= byte,
+            }
+        }
+        assert!(overflow == 0, "value does not fit its encoding");
+        bytes
+    }
+
+    fn same_group(&self, other: &Scalar) {
+        assert!(self.q == other.q, "scalars mod different q");
+    }
+}
+
+// Overwrites the limbs in place, so the whole buffer is wiped and the
+// scalar reads as zero afterwards
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.limbs.as_mut_slice().zeroize();
+    }
+}
+
+// The value only: q is public and usually long
+impl fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Scalar({:#x})", self.value())
+    }
+}
+
+impl Add for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, other: &Scalar) -> Scalar {
+        self.same_group(other);
+        Scalar::new(self.value() + other.value(), &self.q)
+    }
+}
+
+impl Sub for &Scalar {
+    type Output = Scalar;
+
+    // both sides are below q, so adding q first keeps the difference positive
+    fn sub(self, other: &Scalar) -> Scalar {
+        self.same_group(other);
+        Scalar::new(self.value() + &self.q - other.value(), &self.q)
+    }
+}
+
+impl Mul for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, other: &Scalar) -> Scalar {
+        self.same_group(other);
+        Scalar::new(self.value() * other.value(), &self.q)
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar::new(&self.q - self.value(), &self.q)
+    }
+}
+
+// Owned operands, forwarding to the reference impls
+macro_rules! forward_owned {
+    ($($op:ident $method:ident),*) => {
+        $(
+            impl $op for Scalar {
+                type Output = Scalar;
+
+                fn $method(self, other: Scalar) -> Scalar {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add add, Sub sub, Mul mul);
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let q = BigUint::from(11u32);
+        let s = |n: u32| Scalar::new(BigUint::from(n), &q);
+
+        assert_eq!(s(14), s(3));
+        assert_eq!(s(7) + s(6), s(2));
+        assert_eq!(s(3) - s(5), s(9));
+        assert_eq!(s(4) * s(5), s(9));
+        assert_eq!(-s(4), s(7));
+        assert_eq!(-s(0), s(0));
+        assert_eq!(&s(4) - &s(4), s(0));
+
+        assert_eq!(Scalar::from_reduced(BigUint::from(10u32), &q), Some(s(10)));
+        assert_eq!(Scalar::from_reduced(BigUint::from(11u32), &q), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "scalars mod different q")]
+    fn test_mixed_groups() {
+        let a = Scalar::new(BigUint::from(1u32), &BigUint::from(11u32));
+        let b = Scalar::new(BigUint::from(1u32), &BigUint::from(13u32));
+        let _ = a + b;
+    }
+
+    #[test]
+    fn test_random_is_reduced() {
+        let q = BigUint::from(11u32);
+        let mut rng = rand::thread_rng();
+        let mut seen = [false; 11];
+        for _ in 0..1000 {
+            let s = Scalar::random(&q, &mut rng);
+            assert!(s.value() < q);
+            seen[s.value().to_u32_digits().first().copied().unwrap_or(0) as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn test_zeroize_wipes_whole_buffer() {
+        // 130 bits of q take three limbs, whatever the value
+        let q = (BigUint::from(1u32) << 129u32) + 51u32;
+        for value in [BigUint::from(1u32), &q - 1u32] {
+            let mut s = Scalar::new(value, &q);
+            assert_eq!(s.limbs.len(), 3);
+            assert_eq!(s.limbs.capacity(), 3);
+            let buffer = s.limbs.as_ptr();
+
+            s.zeroize();
+            assert_eq!(s.limbs.as_ptr(), buffer);
+            assert_eq!(s.limbs, [0, 0, 0]);
+            assert_eq!(s, Scalar::new(BigUint::from(0u32), &q));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+
+use num_bigint::BigUint;
+
+use super::ct;
+use super::prime::is_probable_prime;
+use crate::ZKP;
+
+/// Requirements a set of public parameters has to meet.
+#[derive(Debug, Clone)]
+pub struct ValidationPolicy {
+    pub min_p_bits: u64,
+    pub min_q_bits: u64,
+    // Miller-Rabin rounds for the primality tests on p and q
+    pub prime_rounds: usize,
+}
+
+impl Default for ValidationPolicy {
+    // Matches the 1024-bit configuration shipped with the crate
+    fn default() -> Self {
+        ValidationPolicy {
+            min_p_bits: 1024,
+            min_q_bits: 160,
+            prime_rounds: 40,
+        }
+    }
+}
+
+/// Reason a set of public parameters was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    PTooSmall { bits: u64, min_bits: u64 },
+    QTooSmall { bits: u64, min_bits: u64 },
+    // p or q is even or wider than the constant-time arithmetic handles,
+    // which secrets and nonces are never raised or combined without
+    UnsupportedModulus(&'static str),
+    PNotPrime,
+    QNotPrime,
+    QDoesNotDivideOrder,
+    // alpha or beta is 0, 1, or not below p
+    TrivialGenerator(&'static str),
+    // alpha or beta does not have order q
+    WrongGeneratorOrder(&'static str),
+    EqualGenerators,
+    // challenges are drawn below challenge_bound, which must leave a choice
+    EmptyChallengeSpace,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::PTooSmall { bits, min_bits } => {
+                write!(f, "p has {} bits, at least {} required", bits, min_bits)
+            }
+            ParamError::QTooSmall { bits, min_bits } => {
+                write!(f, "q has {} bits, at least {} required", bits, min_bits)
+            }
+            ParamError::UnsupportedModulus(name) => {
+                write!(f, "{} must be odd and at most {} bits", name, ct::MAX_BITS)
+            }
+            ParamError::PNotPrime => write!(f, "p is not prime"),
+            ParamError::QNotPrime => write!(f, "q is not prime"),
+            ParamError::QDoesNotDivideOrder => write!(f, "q does not divide p - 1"),
+            ParamError::TrivialGenerator(name) => {
+                write!(f, "{} must be in the range [2, p - 1]", name)
+            }
+            ParamError::WrongGeneratorOrder(name) => {
+                write!(f, "{} does not generate the subgroup of order q", name)
+            }
+            ParamError::EqualGenerators => write!(f, "alpha and beta must be distinct"),
+            ParamError::EmptyChallengeSpace => {
+                write!(f, "challenge_bound must be at least 2")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl ZKP {
+    // Create a new ZKP instance, rejecting parameters that fail the default policy
+    pub fn new_checked(
+        alpha: BigUint,
+        beta: BigUint,
+        p: BigUint,
+        q: BigUint,
+        challenge_bound: BigUint,
+    ) -> Result<ZKP, ParamError> {
+        let zkp = ZKP::new(alpha, beta, p, q, challenge_bound);
+        zkp.validate()?;
+        Ok(zkp)
+    }
+
+    // Check the public parameters against the default policy
+    pub fn validate(&self) -> Result<(), ParamError> {
+        self.validate_with(&ValidationPolicy::default())
+    }
+
+    /// Check that p and q are primes of sufficient size, q divides p - 1,
+    /// and alpha and beta are distinct generators of the order-q subgroup.
+    pub fn validate_with(&self, policy: &ValidationPolicy) -> Result<(), ParamError> {
+        let (p, q) = (&self.group.p, &self.group.q);
+        let one = BigUint::from(1u32);
+
+        if p.bits() < policy.min_p_bits {
+            return Err(ParamError::PTooSmall {
+                bits: p.bits(),
+                min_bits: policy.min_p_bits,
+            });
+        }
+        if q.bits() < policy.min_q_bits {
+            return Err(ParamError::QTooSmall {
+                bits: q.bits(),
+                min_bits: policy.min_q_bits,
+            });
+        }
+        for (name, n) in [("p", p), ("q", q)] {
+            if !n.bit(0) || n.bits() > ct::MAX_BITS {
+                return Err(ParamError::UnsupportedModulus(name));
+            }
+        }
+        if !is_probable_prime(p, policy.prime_rounds) {
+            return Err(ParamError::PNotPrime);
+        }
+        if !is_probable_prime(q, policy.prime_rounds) {
+            return Err(ParamError::QNotPrime);
+        }
+        if (p - &one) % q != BigUint::from(0u32) {
+            return Err(ParamError::QDoesNotDivideOrder);
+        }
+
+        for (name, g) in [("alpha", &self.alpha), ("beta", &self.beta)] {
+            if *g <= one || g >= p {
+                return Err(ParamError::TrivialGenerator(name));
+            }
+            // q is prime, so g^q == 1 with g != 1 means g has order exactly q
+            if g.modpow(q, p) != one {
+                return Err(ParamError::WrongGeneratorOrder(name));
+            }
+        }
+        if self.alpha == self.beta {
+            return Err(ParamError::EqualGenerators);
+        }
+
+        if self.group.challenge_bound < BigUint::from(2u32) {
+            return Err(ParamError::EmptyChallengeSpace);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // No size requirement, so the toy group from the protocol tests qualifies
+    fn toy_policy() -> ValidationPolicy {
+        ValidationPolicy {
+            min_p_bits: 0,
+            min_q_bits: 0,
+            prime_rounds: 20,
+        }
+    }
+
+    fn toy(alpha: u32, beta: u32, p: u32, q: u32) -> ZKP {
+        ZKP::new(
+            BigUint::from(alpha),
+            BigUint::from(beta),
+            BigUint::from(p),
+            BigUint::from(q),
+            BigUint::from(q),
+        )
+    }
+
+    #[test]
+    fn test_1024_bits_config_is_valid() {
+        let (alpha, beta, p, q, challenge_bound) = ZKP::get_1024_bits_config().unwrap();
+        assert!(ZKP::new_checked(alpha, beta, p, q, challenge_bound).is_ok());
+    }
+
+    #[test]
+    fn test_toy_example_is_valid() {
+        assert_eq!(toy(4, 9, 23, 11).validate_with(&toy_policy()), Ok(()));
+    }
+
+    #[test]
+    fn test_bit_size_policy() {
+        assert_eq!(
+            toy(4, 9, 23, 11).validate(),
+            Err(ParamError::PTooSmall {
+                bits: 5,
+                min_bits: 1024
+            })
+        );
+        let policy = ValidationPolicy {
+            min_q_bits: 5,
+            ..toy_policy()
+        };
+        assert_eq!(
+            toy(4, 9, 23, 11).validate_with(&policy),
+            Err(ParamError::QTooSmall {
+                bits: 4,
+                min_bits: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_parameters() {
+        let policy = toy_policy();
+        assert_eq!(
+            toy(4, 9, 21, 11).validate_with(&policy),
+            Err(ParamError::PNotPrime)
+        );
+        assert_eq!(
+            toy(4, 9, 23, 9).validate_with(&policy),
+            Err(ParamError::QNotPrime)
+        );
+        assert_eq!(
+            toy(4, 9, 23, 7).validate_with(&policy),
+            Err(ParamError::QDoesNotDivideOrder)
+        );
+        assert_eq!(
+            toy(1, 9, 23, 11).validate_with(&policy),
+            Err(ParamError::TrivialGenerator("alpha"))
+        );
+        assert_eq!(
+            toy(4, 23, 23, 11).validate_with(&policy),
+            Err(ParamError::TrivialGenerator("beta"))
+        );
+        // 5 generates the whole group of order 22
+        assert_eq!(
+            toy(5, 9, 23, 11).validate_with(&policy),
+            Err(ParamError::WrongGeneratorOrder("alpha"))
+        );
+        // 22 = -1 has order 2
+        assert_eq!(
+            toy(4, 22, 23, 11).validate_with(&policy),
+            Err(ParamError::WrongGeneratorOrder("beta"))
+        );
+        assert_eq!(
+            toy(4, 4, 23, 11).validate_with(&policy),
+            Err(ParamError::EqualGenerators)
+        );
+
+        // q = 2 is prime and divides 6, but no odd modulus
+        assert_eq!(
+            toy(6, 5, 7, 2).validate_with(&policy),
+            Err(ParamError::UnsupportedModulus("q"))
+        );
+        let mut zkp = toy(4, 9, 23, 11);
+        zkp.group.p = (BigUint::from(1u32) << ct::MAX_BITS) + 1u32;
+        assert_eq!(
+            zkp.validate_with(&policy),
+            Err(ParamError::UnsupportedModulus("p"))
+        );
+
+        let mut zkp = toy(4, 9, 23, 11);
+        zkp.group.challenge_bound = BigUint::from(1u32);
+        assert_eq!(
+            zkp.validate_with(&policy),
+            Err(ParamError::EmptyChallengeSpace)
+        );
+    }
+}
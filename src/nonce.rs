@@ -0,0 +1,278 @@
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha512;
+use zeroize::Zeroize;
+
+use crate::fiat_shamir::FiatShamir;
+use crate::group::Group;
+use crate::{Nonce, SecretKey};
+
+// Fresh random bytes mixed into the seed in hedged mode
+const HEDGE_LEN: usize = 32;
+
+/// How the prover draws the nonce k behind a commitment.
+///
+/// `Deterministic` derives k in the style of RFC 6979 from x, the statement
+/// (group, alpha, beta, y1, y2) and a context, so a broken rng cannot leak x.
+/// The same inputs always give the same k, which is only safe if the
+/// challenge depends on the commitment, as in Fiat-Shamir proofs. In an
+/// interactive run a verifier could rewind the prover and send a second
+/// challenge for the same commitment, learning x from the two responses, so
+/// interactive provers use `Hedged` instead. `Hedged` mixes fresh
+/// randomness into the same derivation and is safe in both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonceMode {
+    // k straight from the rng
+    #[default]
+    Random,
+    // k from HMAC-DRBG over x, the statement and the context
+    Deterministic,
+    // as Deterministic, with HEDGE_LEN bytes from the rng in the seed
+    Hedged,
+}
+
+impl NonceMode {
+    // The mode an interactive prover actually uses: the challenge there does
+    // not depend on the commitment, so Deterministic falls back to Hedged
+    pub(crate) fn interactive(self) -> Self {
+        match self {
+            NonceMode::Deterministic => NonceMode::Hedged,
+            mode => mode,
+        }
+    }
+}
+
+/// HMAC-DRBG from NIST SP 800-90A, instantiated as in RFC 6979 section 3.2
+/// and never reseeded. The state is wiped on drop.
+pub(crate) struct HmacDrbg<D: Digest + BlockSizeUser> {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    hash: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest + BlockSizeUser> HmacDrbg<D> {
+    // K = 0x00.., V = 0x01.., then update with the concatenated seed
+    pub(crate) fn new(seed: &[&[u8]]) -> Self {
+        let len = <D as Digest>::output_size();
+        let mut drbg = HmacDrbg {
+            k: vec![0x00; len],
+            v: vec![0x01; len],
+            hash: std::marker::PhantomData,
+        };
+        drbg.update(seed);
+        drbg
+    }
+
+    fn hmac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac =
+            <SimpleHmac<D> as Mac>::new_from_slice(&self.k).expect("HMAC takes keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn update(&mut self, seed: &[&[u8]]) {
+        for (round, byte) in [0x00u8, 0x01].into_iter().enumerate() {
+            if round == 1 && seed.iter().all(|part| part.is_empty()) {
+                break;
+            }
+            let byte = [byte];
+            let mut parts: Vec<&[u8]> = vec![&self.v, &byte];
+            parts.extend_from_slice(seed);
+            let k = self.hmac(&parts);
+            self.k.zeroize();
+            self.k = k;
+            let v = self.hmac(&[&self.v]);
+            self.v.zeroize();
+            self.v = v;
+        }
+    }
+
+    pub(crate) fn fill_bytes(&mut self, out: &mut [u8]) {
+        for chunk in out.chunks_mut(self.v.len()) {
+            let v = self.hmac(&[&self.v]);
+            self.v.zeroize();
+            self.v = v;
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[]);
+    }
+}
+
+impl<D: Digest + BlockSizeUser> Drop for HmacDrbg<D> {
+    fn drop(&mut self) {
+        self.k.zeroize();
+        self.v.zeroize();
+    }
+}
+
+// Nonce for a commitment by the prover holding x, drawn as the mode says.
+// The statement is only hashed for the derived modes
+pub(crate) fn nonce_with_mode<G: Group, R: RngCore + CryptoRng>(
+    group: &G,
+    mode: NonceMode,
+    x: &SecretKey<G>,
+    statement: impl FnOnce() -> FiatShamir,
+    rng: &mut R,
+) -> Nonce<G> {
+    match mode {
+        NonceMode::Random => Nonce::random(group, rng),
+        NonceMode::Deterministic => derive_nonce(group, x, statement(), &[]),
+        NonceMode::Hedged => {
+            let mut extra = [0u8; HEDGE_LEN];
+            rng.fill_bytes(&mut extra);
+            derive_nonce(group, x, statement(), &extra)
+        }
+    }
+}
+
+// k = HMAC-DRBG-SHA512(x || H(statement, context) || extra), reduced
+// from `uniform_bytes_len` output bytes like a password-derived secret
+fn derive_nonce<G: Group>(
+    group: &G,
+    x: &SecretKey<G>,
+    statement: FiatShamir,
+    extra: &[u8],
+) -> Nonce<G> {
+    let mut x_bytes = group.scalar_to_bytes(x.expose());
+    let mut drbg = HmacDrbg::<Sha512>::new(&[&x_bytes, &statement.digest(), extra]);
+    x_bytes.zeroize();
+    let mut okm = vec![0u8; group.uniform_bytes_len()];
+    drbg.fill_bytes(&mut okm);
+    let k = Nonce::new(group.scalar_from_uniform_bytes(&okm));
+    okm.zeroize();
+    k
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Sigma;
+    use crate::{ChaumPedersen, ParamSet, Protocol, Prover};
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use sha2::Sha256;
+
+    // RFC 6979 appendix A.2.5: P-256 with SHA-256, message "sample"
+    #[test]
+    fn test_rfc6979_p256_sha256() {
+        let q = BigUint::from_bytes_be(
+            &hex::decode("FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551")
+                .unwrap(),
+        );
+        let x = hex::decode("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721")
+            .unwrap();
+        // bits2octets(H(m)): the hash reduced mod q
+        let h1 = BigUint::from_bytes_be(&Sha256::digest(b"sample")) % &q;
+        let mut h1 = h1.to_bytes_be();
+        while h1.len() < 32 {
+            h1.insert(0, 0);
+        }
+
+        let mut drbg = HmacDrbg::<Sha256>::new(&[&x, &h1]);
+        let mut k = [0u8; 32];
+        drbg.fill_bytes(&mut k);
+        // the first candidate is below q, so no retry is needed
+        assert!(BigUint::from_bytes_be(&k) < q);
+        assert_eq!(
+            hex::encode_upper(k),
+            "A6E3C57DD01ABE90086538398355DD4C3B17AA873382B0F24D6129493D8AAD60"
+        );
+    }
+
+    fn nonce<G: Group>(
+        zkp: &ChaumPedersen<G>,
+        mode: NonceMode,
+        x: &SecretKey<G>,
+        context: &[u8],
+        seed: u8,
+    ) -> G::Scalar {
+        let mut rng = ChaCha20Rng::from_seed([seed; 32]);
+        zkp.nonce_with_mode(mode, x, context, &mut rng)
+            .expose()
+            .clone()
+    }
+
+    #[test]
+    fn test_modes() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let x = zkp.generate_secret_key();
+        let other = zkp.generate_secret_key();
+        let det = |x, context: &[u8], seed| nonce(&zkp, NonceMode::Deterministic, x, context, seed);
+
+        // deterministic ignores the rng, but not x or the context
+        assert_eq!(det(&x, b"alice", 1), det(&x, b"alice", 2));
+        assert_ne!(det(&x, b"alice", 1), det(&x, b"bob", 1));
+        assert_ne!(det(&x, b"alice", 1), det(&other, b"alice", 1));
+        assert!(det(&x, b"alice", 1).value() < zkp.group.q);
+
+        // hedged depends on both
+        let hedged = |seed| nonce(&zkp, NonceMode::Hedged, &x, b"alice", seed);
+        assert_eq!(hedged(1), hedged(1));
+        assert_ne!(hedged(1), hedged(2));
+        assert_ne!(hedged(1), det(&x, b"alice", 1));
+    }
+
+    // Known answers for x = 6, pinning the whole derivation
+    #[test]
+    fn test_known_answers() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let x = SecretKey::new(zkp.group.scalar(BigUint::from(6u32)));
+        let k = nonce(&zkp, NonceMode::Deterministic, &x, b"alice", 0);
+        assert_eq!(
+            hex::encode(zkp.group.scalar_to_bytes(&k)),
+            "acc11fd8be0a79a27f6e295feab9fd98eb7c9a34"
+        );
+
+        #[cfg(feature = "ristretto")]
+        {
+            let zkp = crate::RistrettoZKP::ristretto255();
+            let x = SecretKey::new(curve25519_dalek::Scalar::from(6u32));
+            let proof = zkp.prove_non_interactive_with_mode(
+                &x,
+                b"alice",
+                NonceMode::Deterministic,
+                &mut rand::thread_rng(),
+            );
+            let expected = concat!(
+                "0000002054b5b6d90a6832bb241c6e5a7bca2d74c9497a3961cdfbf3db6a083a72ac2710",
+                "0000002046de103f9acf321717a0bbe05463a2217e3b4e8d3888684ba05f6d11fecfd00c",
+                "00000020fdb55eefb7df4a99ee35e197999caaef42e45a4b6bfe8defad7960293a7cfb0b",
+            );
+            assert_eq!(hex::encode(proof.to_bytes(&zkp.group)), expected);
+        }
+    }
+
+    #[test]
+    fn test_prover_modes() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let commit = |mode| {
+            let x = SecretKey::new(zkp.group.scalar(BigUint::from(6u32)));
+            let prover = Prover::new(&zkp, x)
+                .with_nonce_mode(mode)
+                .with_context(b"session 1");
+            let (commitment, prover) = prover.commit();
+            let c = zkp.generate_random();
+            let response = prover.respond(&c);
+            let x = SecretKey::new(zkp.group.scalar(BigUint::from(6u32)));
+            let (y1, y2) = zkp.exponentiate(&x);
+            let r1 = commitment.r1.clone();
+            let r2 = commitment.r2;
+            let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+            assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &response.s, &c), Ok(()));
+            commitment.r1
+        };
+        // a rewound interactive prover must not repeat its commitment, so
+        // Deterministic is hedged
+        assert_ne!(
+            commit(NonceMode::Deterministic),
+            commit(NonceMode::Deterministic)
+        );
+        assert_ne!(commit(NonceMode::Hedged), commit(NonceMode::Hedged));
+        assert_ne!(commit(NonceMode::Random), commit(NonceMode::Random));
+    }
+}
@@ -0,0 +1,552 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::composition::{unchecked, CheckedStatement, Statement};
+use crate::fiat_shamir::{decode_fields, encode_fields};
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::{ChaumPedersen, Commitment, Nonce, Protocol, SecretKey, ZkpError};
+
+// The simulated (c_i, s_i) of every branch, None at the real one
+type Simulated<G> = Vec<Option<(<G as Group>::Scalar, <G as Group>::Scalar)>>;
+
+/// The prover's answer to an OR challenge c: a challenge share c_i and a
+/// response s_i per branch, with the shares summing to c.
+pub struct OrResponse<G: Group> {
+    pub c: Vec<G::Scalar>,
+    pub s: Vec<G::Scalar>,
+}
+
+/// Non-interactive OR proof: one commitment per branch and the response.
+pub struct OrProof<G: Group> {
+    pub commitments: Vec<Commitment<G>>,
+    pub response: OrResponse<G>,
+}
+
+impl<G: Group> Clone for OrResponse<G> {
+    fn clone(&self) -> Self {
+        OrResponse {
+            c: self.c.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: Group> fmt::Debug for OrResponse<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrResponse")
+            .field("c", &self.c)
+            .field("s", &self.s)
+            .finish()
+    }
+}
+
+impl<G: Group> Clone for OrProof<G> {
+    fn clone(&self) -> Self {
+        OrProof {
+            commitments: self.commitments.clone(),
+            response: self.response.clone(),
+        }
+    }
+}
+
+impl<G: Group> fmt::Debug for OrProof<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrProof")
+            .field("commitments", &self.commitments)
+            .field("response", &self.response)
+            .finish()
+    }
+}
+
+impl<G: Group> PartialEq for OrProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.commitments == other.commitments
+            && self.response.c == other.response.c
+            && self.response.s == other.response.s
+    }
+}
+
+impl<G: Group> OrProof<G> {
+    // Length-prefixed r1_i || r2_i || c_i || s_i for every branch in turn
+    pub fn to_bytes(&self, group: &G) -> Vec<u8> {
+        let mut fields = Vec::with_capacity(4 * self.commitments.len());
+        let branches = self
+            .commitments
+            .iter()
+            .zip(&self.response.c)
+            .zip(&self.response.s);
+        for ((Commitment { r1, r2 }, c), s) in branches {
+            fields.push(group.element_to_bytes(r1));
+            fields.push(group.element_to_bytes(r2));
+            fields.push(group.scalar_to_bytes(c));
+            fields.push(group.scalar_to_bytes(s));
+        }
+        encode_fields(&fields)
+    }
+
+    // A proof with the given number of branches
+    pub fn from_bytes(group: &G, branches: usize, bytes: &[u8]) -> Result<OrProof<G>, ZkpError> {
+        let fields = decode_fields(bytes, 4 * branches).ok_or(ZkpError::Decoding("proof"))?;
+        let element = |bytes, name| {
+            group
+                .element_from_bytes(bytes)
+                .ok_or(ZkpError::Decoding(name))
+        };
+        let scalar = |bytes, name| {
+            group
+                .scalar_from_bytes(bytes)
+                .ok_or(ZkpError::Decoding(name))
+        };
+        let mut proof = OrProof {
+            commitments: Vec::with_capacity(branches),
+            response: OrResponse {
+                c: Vec::with_capacity(branches),
+                s: Vec::with_capacity(branches),
+            },
+        };
+        for branch in fields.chunks(4) {
+            proof.commitments.push(Commitment {
+                r1: element(branch[0], "r1")?,
+                r2: element(branch[1], "r2")?,
+            });
+            proof.response.c.push(scalar(branch[2], "c")?);
+            proof.response.s.push(scalar(branch[3], "s")?);
+        }
+        Ok(proof)
+    }
+}
+
+impl<G: Group> ChaumPedersen<G> {
+    // c = H(group, alpha, beta, n, y1_i, y2_i and r1_i, r2_i for every
+    // branch, context)
+    pub fn or_challenge(
+        &self,
+        statements: &[Statement<G>],
+        commitments: &[Commitment<G>],
+        context: &[u8],
+    ) -> G::Scalar {
+        self.composition_challenge(b"zkp-chaum-pedersen or", statements, commitments, context)
+    }
+
+    // Prove knowledge of the secret behind statements[index] in one
+    // message, without revealing the index
+    pub fn prove_or_non_interactive(
+        &self,
+        statements: &[Statement<G>],
+        index: usize,
+        x: &SecretKey<G>,
+        context: &[u8],
+    ) -> Result<OrProof<G>, ZkpError> {
+        self.prove_or_non_interactive_with_rng(
+            statements,
+            index,
+            x,
+            context,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    pub fn prove_or_non_interactive_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        statements: &[Statement<G>],
+        index: usize,
+        x: &SecretKey<G>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> Result<OrProof<G>, ZkpError> {
+        if index >= statements.len() {
+            return Err(ZkpError::LengthMismatch("statements"));
+        }
+        let (commitments, k, simulated) = self.or_commit(statements, index, rng);
+        let c = self.or_challenge(statements, &commitments, context);
+        let response = self.or_respond(&k, simulated, x, &c);
+        Ok(OrProof {
+            commitments,
+            response,
+        })
+    }
+
+    // Commit to the real branch with a fresh nonce, and simulate the others
+    // as r1 = alpha^s * y1^c, r2 = beta^s * y2^c. Returns the simulated
+    // (c_i, s_i), None at the index
+    fn or_commit<R: RngCore + CryptoRng>(
+        &self,
+        statements: &[Statement<G>],
+        index: usize,
+        rng: &mut R,
+    ) -> (Vec<Commitment<G>>, Nonce<G>, Simulated<G>) {
+        let group = &self.group;
+        let k = self.generate_nonce(rng);
+        let mut commitments = Vec::with_capacity(statements.len());
+        let mut simulated = Vec::with_capacity(statements.len());
+        for (i, (y1, y2)) in statements.iter().enumerate() {
+            if i == index {
+                let (r1, r2) = self.commit(&k);
+                commitments.push(Commitment { r1, r2 });
+                simulated.push(None);
+                continue;
+            }
+            let c = group.random_scalar(rng);
+            let s = group.random_scalar(rng);
+            let exponents = [s.clone(), c.clone()];
+            commitments.push(Commitment {
+                r1: group.multi_exp(&[self.alpha.clone(), y1.clone()], &exponents),
+                r2: group.multi_exp(&[self.beta.clone(), y2.clone()], &exponents),
+            });
+            simulated.push(Some((c, s)));
+        }
+        (commitments, k, simulated)
+    }
+
+    // The real share is c minus the simulated ones, answered with the nonce
+    fn or_respond(
+        &self,
+        k: &Nonce<G>,
+        simulated: Simulated<G>,
+        x: &SecretKey<G>,
+        c: &G::Scalar,
+    ) -> OrResponse<G> {
+        let group = &self.group;
+        let c_real = simulated
+            .iter()
+            .flatten()
+            .fold(c.clone(), |c, (c_i, _)| group.scalar_sub(&c, c_i));
+        let s_real = self.solve(k, &c_real, x);
+        let (c, s) = simulated
+            .into_iter()
+            .map(|branch| branch.unwrap_or_else(|| (c_real.clone(), s_real.clone())))
+            .unzip();
+        OrResponse { c, s }
+    }
+
+    pub fn verify_or_non_interactive(
+        &self,
+        statements: &[CheckedStatement<G>],
+        proof: &OrProof<G>,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        let commitments = self.check_commitments(statements.len(), proof.commitments.clone())?;
+        let c = self.or_challenge(&unchecked(statements), &proof.commitments, context);
+        self.verify_or(statements, &commitments, &proof.response, &c)
+    }
+
+    // The shares must sum to c, and every branch must verify with its share
+    fn verify_or(
+        &self,
+        statements: &[CheckedStatement<G>],
+        commitments: &[CheckedStatement<G>],
+        response: &OrResponse<G>,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        let group = &self.group;
+        let branches = statements.len();
+        if response.c.len() != branches {
+            return Err(ZkpError::LengthMismatch("c"));
+        }
+        if response.s.len() != branches {
+            return Err(ZkpError::LengthMismatch("s"));
+        }
+        let mut sum = None;
+        for c_i in &response.c {
+            if !group.scalar_in_range(c_i) {
+                return Err(ZkpError::ScalarOutOfRange("c"));
+            }
+            sum = Some(match sum {
+                Some(sum) => group.scalar_add(&sum, c_i),
+                None => c_i.clone(),
+            });
+        }
+        if sum.as_ref() != Some(c) {
+            return Err(ZkpError::VerificationFailed);
+        }
+        let branches = statements.iter().zip(commitments);
+        for (((y1, y2), (r1, r2)), (c_i, s_i)) in branches.zip(response.c.iter().zip(&response.s)) {
+            self.verify(y1, y2, r1, r2, s_i, c_i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prover side of one interactive OR run: knows the secret behind one of
+/// several (y1, y2) pairs and convinces the verifier without saying which.
+///
+/// The other branches are simulated, with a challenge share and response
+/// drawn up front; the real branch gets whatever is left of the verifier's
+/// challenge. Shares are drawn uniformly from Z_q rather than the smaller
+/// challenge space, so the real share looks like the simulated ones. The
+/// index is kept secret from the verifier but the prover's running time
+/// may depend on it, and nonces are always drawn from the rng.
+pub struct OrProver<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    statements: Vec<Statement<G>>,
+    index: usize,
+    x: SecretKey<G>,
+}
+
+/// An OR prover that sent its commitments and waits for the challenge.
+pub struct OrAwaitingChallenge<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    x: SecretKey<G>,
+    k: Nonce<G>,
+    simulated: Simulated<G>,
+}
+
+impl<'a, G: Group> OrProver<'a, G> {
+    // x must be the secret of statements[index]. Fails if there is no such
+    // statement
+    pub fn new(
+        zkp: &'a ChaumPedersen<G>,
+        statements: Vec<Statement<G>>,
+        index: usize,
+        x: SecretKey<G>,
+    ) -> Result<Self, ZkpError> {
+        if index >= statements.len() {
+            return Err(ZkpError::LengthMismatch("statements"));
+        }
+        Ok(OrProver {
+            zkp,
+            statements,
+            index,
+            x,
+        })
+    }
+
+    pub fn commit(self) -> (Vec<Commitment<G>>, OrAwaitingChallenge<'a, G>) {
+        self.commit_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+    ) -> (Vec<Commitment<G>>, OrAwaitingChallenge<'a, G>) {
+        let (commitments, k, simulated) = self.zkp.or_commit(&self.statements, self.index, rng);
+        let next = OrAwaitingChallenge {
+            zkp: self.zkp,
+            x: self.x,
+            k,
+            simulated,
+        };
+        (commitments, next)
+    }
+}
+
+impl<G: Group> OrAwaitingChallenge<'_, G> {
+    // Answer the challenge, wiping the nonce
+    pub fn respond(self, c: &G::Scalar) -> OrResponse<G> {
+        self.zkp.or_respond(&self.k, self.simulated, &self.x, c)
+    }
+}
+
+/// Verifier side of one interactive OR run against registered (y1, y2)
+/// pairs.
+pub struct OrVerifier<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    statements: Vec<CheckedStatement<G>>,
+}
+
+/// An OR verifier that issued its challenge and waits for the response.
+pub struct OrAwaitingResponse<'a, G: Group> {
+    zkp: &'a ChaumPedersen<G>,
+    statements: Vec<CheckedStatement<G>>,
+    commitments: Vec<CheckedStatement<G>>,
+    c: G::Scalar,
+}
+
+impl<'a, G: Group> OrVerifier<'a, G> {
+    pub fn new(zkp: &'a ChaumPedersen<G>, statements: Vec<CheckedStatement<G>>) -> Self {
+        OrVerifier { zkp, statements }
+    }
+
+    // Check the commitments, one per statement, and draw the challenge
+    pub fn challenge(
+        self,
+        commitments: Vec<Commitment<G>>,
+    ) -> Result<(G::Scalar, OrAwaitingResponse<'a, G>), ZkpError> {
+        self.challenge_with_rng(commitments, &mut rand::thread_rng())
+    }
+
+    pub fn challenge_with_rng<R: RngCore + CryptoRng>(
+        self,
+        commitments: Vec<Commitment<G>>,
+        rng: &mut R,
+    ) -> Result<(G::Scalar, OrAwaitingResponse<'a, G>), ZkpError> {
+        let commitments = self
+            .zkp
+            .check_commitments(self.statements.len(), commitments)?;
+        let c = self.zkp.generate_random_with_rng(rng);
+        let next = OrAwaitingResponse {
+            zkp: self.zkp,
+            statements: self.statements,
+            commitments,
+            c: c.clone(),
+        };
+        Ok((c, next))
+    }
+}
+
+impl<G: Group> OrAwaitingResponse<'_, G> {
+    pub fn verify(self, response: &OrResponse<G>) -> Result<(), ZkpError> {
+        self.zkp
+            .verify_or(&self.statements, &self.commitments, response, &self.c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::modp::Scalar;
+    use crate::{ModPGroup, ParamSet};
+
+    // n registered users, returning the secret of one of them
+    fn members<G: Group>(
+        zkp: &ChaumPedersen<G>,
+        n: usize,
+        index: usize,
+    ) -> (Vec<Statement<G>>, Vec<CheckedStatement<G>>, SecretKey<G>) {
+        let mut secret = None;
+        let mut statements = Vec::new();
+        for i in 0..n {
+            let x = zkp.generate_secret_key();
+            statements.push(zkp.exponentiate(&x));
+            if i == index {
+                secret = Some(x);
+            }
+        }
+        let checked = statements
+            .iter()
+            .map(|(y1, y2)| {
+                (
+                    zkp.check_element("y1", y1.clone()).unwrap(),
+                    zkp.check_element("y2", y2.clone()).unwrap(),
+                )
+            })
+            .collect();
+        (statements, checked, secret.unwrap())
+    }
+
+    // The prover at every index convinces the verifier, interactively and
+    // not, to cover every group
+    fn run<G: Group>(zkp: &ChaumPedersen<G>) {
+        for index in 0..3 {
+            let (statements, checked, x) = members(zkp, 3, index);
+            let proof = zkp
+                .prove_or_non_interactive(&statements, index, &x, b"members")
+                .unwrap();
+            assert_eq!(
+                zkp.verify_or_non_interactive(&checked, &proof, b"members"),
+                Ok(())
+            );
+
+            let prover = OrProver::new(zkp, statements, index, x).unwrap();
+            let verifier = OrVerifier::new(zkp, checked);
+            let (commitments, prover) = prover.commit();
+            let (c, verifier) = verifier.challenge(commitments).unwrap();
+            assert_eq!(verifier.verify(&prover.respond(&c)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_or_modp() {
+        run(&ParamSet::Rfc5114_1024_160.zkp().unwrap());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_or_ristretto() {
+        run(&crate::RistrettoZKP::ristretto255());
+    }
+
+    // a secret that belongs to none of the members
+    #[test]
+    fn test_outsider() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let (statements, checked, _) = members(&zkp, 3, 0);
+        let prover = OrProver::new(&zkp, statements, 1, zkp.generate_secret_key()).unwrap();
+        let verifier = OrVerifier::new(&zkp, checked);
+        let (commitments, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(commitments).unwrap();
+        assert_eq!(
+            verifier.verify(&prover.respond(&c)),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    // With one branch nothing is simulated and the share is c itself
+    #[test]
+    fn test_single_branch() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let (statements, checked, x) = members(&zkp, 1, 0);
+        let prover = OrProver::new(&zkp, statements, 0, x).unwrap();
+        let verifier = OrVerifier::new(&zkp, checked);
+        let (commitments, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(commitments).unwrap();
+        let response = prover.respond(&c);
+        assert_eq!(response.c, vec![c]);
+        assert_eq!(verifier.verify(&response), Ok(()));
+    }
+
+    // The same key registered twice: either index proves it
+    #[test]
+    fn test_duplicate_statements() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let (statements, checked, x) = members(&zkp, 1, 0);
+        let statements = vec![statements[0].clone(), statements[0].clone()];
+        let checked = vec![checked[0].clone(), checked[0].clone()];
+        for index in 0..2 {
+            let x = SecretKey::new(x.expose().clone());
+            let proof = zkp
+                .prove_or_non_interactive(&statements, index, &x, b"members")
+                .unwrap();
+            assert_eq!(
+                zkp.verify_or_non_interactive(&checked, &proof, b"members"),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_shares() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let (statements, checked, x) = members(&zkp, 3, 1);
+        let proof = zkp
+            .prove_or_non_interactive(&statements, 1, &x, b"members")
+            .unwrap();
+        let verify =
+            |proof: &OrProof<_>| zkp.verify_or_non_interactive(&checked, proof, b"members");
+
+        // shares that no longer sum to the challenge
+        let mut tampered = proof.clone();
+        tampered.response.c[0] = tampered.response.c[1].clone();
+        assert_eq!(verify(&tampered), Err(ZkpError::VerificationFailed));
+
+        // a share mod another q
+        let mut tampered = proof.clone();
+        let other_q = &zkp.group.q + 2u32;
+        tampered.response.c[2] = Scalar::new(proof.response.c[2].value().clone(), &other_q);
+        assert_eq!(verify(&tampered), Err(ZkpError::ScalarOutOfRange("c")));
+
+        let mut tampered = proof.clone();
+        tampered.response.c.pop();
+        assert_eq!(verify(&tampered), Err(ZkpError::LengthMismatch("c")));
+
+        // the encoding does not carry the count, the verifier supplies it
+        let bytes = proof.to_bytes(&zkp.group);
+        assert_eq!(OrProof::from_bytes(&zkp.group, 3, &bytes), Ok(proof));
+        assert_eq!(
+            OrProof::<ModPGroup>::from_bytes(&zkp.group, 2, &bytes),
+            Err(ZkpError::Decoding("proof"))
+        );
+    }
+
+    #[test]
+    fn test_index_out_of_range() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let (statements, _, x) = members(&zkp, 2, 0);
+        assert_eq!(
+            OrProver::new(&zkp, statements, 2, x).map(|_| ()),
+            Err(ZkpError::LengthMismatch("statements"))
+        );
+    }
+}
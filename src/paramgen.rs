@@ -0,0 +1,43 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use zkp_chaum_pedersen::DomainParameters;
+
+// Generate fresh (p, q, alpha, beta) and write them to a parameter file
+// cargo run --release --bin paramgen -- <p bits> <q bits> <output file>
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 {
+        eprintln!("usage: {} <p bits> <q bits> <output file>", args[0]);
+        eprintln!("example: {} 2048 256 params.txt", args[0]);
+        process::exit(2);
+    }
+    let (p_bits, q_bits): (usize, usize) = match (args[1].parse(), args[2].parse()) {
+        (Ok(p_bits), Ok(q_bits)) => (p_bits, q_bits),
+        _ => {
+            eprintln!("p bits and q bits must be numbers");
+            process::exit(2);
+        }
+    };
+
+    println!("Generating {p_bits}-bit p with {q_bits}-bit q, this can take a while...");
+    let params = match DomainParameters::generate(p_bits, q_bits) {
+        Ok(params) => params,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(err) = fs::write(&args[3], params.to_text()) {
+        eprintln!("could not write parameter file {}: {err}", args[3]);
+        process::exit(1);
+    }
+    println!(
+        "✔️ Wrote parameters to {} (seed {}, counter {})",
+        args[3],
+        hex::encode(&params.seed),
+        params.counter
+    );
+}
@@ -0,0 +1,434 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use num_bigint::BigUint;
+
+use crate::dynamic::DynChaumPedersen;
+use crate::modp::{derive_generator, BETA_INDEX, BETA_SEED};
+use crate::ZKP;
+
+// RFC 5114, section 2.1: 1024-bit MODP group with 160-bit prime order subgroup
+const RFC5114_1024_P: &str = concat!(
+    "B10B8F96A080E01DDE92DE5EAE5D54EC52C99FBCFB06A3C69A6A9DCA52D23B61",
+    "6073E28675A23D189838EF1E2EE652C013ECB4AEA906112324975C3CD49B83BF",
+    "ACCBDD7D90C4BD7098488E9C219A73724EFFD6FAE5644738FAA31A4FF55BCCC0",
+    "A151AF5F0DC8B4BD45BF37DF365C1A65E68CFDA76D4DA708DF1FB2BC2E4A4371"
+);
+const RFC5114_1024_Q: &str = "F518AA8781A8DF278ABA4E7D64B7CB9D49462353";
+const RFC5114_1024_G: &str = concat!(
+    "A4D1CBD5C3FD34126765A442EFB99905F8104DD258AC507FD6406CFF14266D31",
+    "266FEA1E5C41564B777E690F5504F213160217B4B01B886A5E91547F9E2749F4",
+    "D7FBD7D3B9A92EE1909D0D2263F80A76A6A24C087A091F531DBF0A0169B6A28A",
+    "D662A4D18E73AFA32D779D5918D08BC8858F4DCEF97C2A24855E6EEB22B3B2E5"
+);
+
+// RFC 5114, section 2.2: 2048-bit MODP group with 224-bit prime order subgroup
+const RFC5114_2048_224_P: &str = concat!(
+    "AD107E1E9123A9D0D660FAA79559C51FA20D64E5683B9FD1B54B1597B61D0A75",
+    "E6FA141DF95A56DBAF9A3C407BA1DF15EB3D688A309C180E1DE6B85A1274A0A6",
+    "6D3F8152AD6AC2129037C9EDEFDA4DF8D91E8FEF55B7394B7AD5B7D0B6C12207",
+    "C9F98D11ED34DBF6C6BA0B2C8BBC27BE6A00E0A0B9C49708B3BF8A3170918836",
+    "81286130BC8985DB1602E714415D9330278273C7DE31EFDC7310F7121FD5A074",
+    "15987D9ADC0A486DCDF93ACC44328387315D75E198C641A480CD86A1B9E587E8",
+    "BE60E69CC928B2B9C52172E413042E9B23F10B0E16E79763C9B53DCF4BA80A29",
+    "E3FB73C16B8E75B97EF363E2FFA31F71CF9DE5384E71B81C0AC4DFFE0C10E64F"
+);
+const RFC5114_2048_224_Q: &str = "801C0D34C58D93FE997177101F80535A4738CEBCBF389A99B36371EB";
+const RFC5114_2048_224_G: &str = concat!(
+    "AC4032EF4F2D9AE39DF30B5C8FFDAC506CDEBE7B89998CAF74866A08CFE4FFE3",
+    "A6824A4E10B9A6F0DD921F01A70C4AFAAB739D7700C29F52C57DB17C620A8652",
+    "BE5E9001A8D66AD7C17669101999024AF4D027275AC1348BB8A762D0521BC98A",
+    "E247150422EA1ED409939D54DA7460CDB5F6C6B250717CBEF180EB34118E98D1",
+    "19529A45D6F834566E3025E316A330EFBB77A86F0C1AB15B051AE3D428C8F8AC",
+    "B70A8137150B8EEB10E183EDD19963DDD9E263E4770589EF6AA21E7F5F2FF381",
+    "B539CCE3409D13CD566AFBB48D6C019181E1BCFE94B30269EDFE72FE9B6AA4BD",
+    "7B5A0F1C71CFFF4C19C418E1F6EC017981BC087F2A7065B384B890D3191F2BFA"
+);
+
+// RFC 5114, section 2.3: 2048-bit MODP group with 256-bit prime order subgroup
+const RFC5114_2048_256_P: &str = concat!(
+    "87A8E61DB4B6663CFFBBD19C651959998CEEF608660DD0F25D2CEED4435E3B00",
+    "E00DF8F1D61957D4FAF7DF4561B2AA3016C3D91134096FAA3BF4296D830E9A7C",
+    "209E0C6497517ABD5A8A9D306BCF67ED91F9E6725B4758C022E0B1EF4275BF7B",
+    "6C5BFC11D45F9088B941F54EB1E59BB8BC39A0BF12307F5C4FDB70C581B23F76",
+    "B63ACAE1CAA6B7902D52526735488A0EF13C6D9A51BFA4AB3AD8347796524D8E",
+    "F6A167B5A41825D967E144E5140564251CCACB83E6B486F6B3CA3F7971506026",
+    "C0B857F689962856DED4010ABD0BE621C3A3960A54E710C375F26375D7014103",
+    "A4B54330C198AF126116D2276E11715F693877FAD7EF09CADB094AE91E1A1597"
+);
+const RFC5114_2048_256_Q: &str = "8CF83642A709A097B447997640129DA299B1A47D1EB3750BA308B0FE64F5FBD3";
+const RFC5114_2048_256_G: &str = concat!(
+    "3FB32C9B73134D0B2E77506660EDBD484CA7B18F21EF205407F4793A1A0BA125",
+    "10DBC15077BE463FFF4FED4AAC0BB555BE3A6C1B0C6B47B1BC3773BF7E8C6F62",
+    "901228F8C28CBB18A55AE31341000A650196F931C77A57F2DDF463E5E9EC144B",
+    "777DE62AAAB8A8628AC376D282D6ED3864E67982428EBC831D14348F6F2F9193",
+    "B5045AF2767164E1DFC967C1FB3F2E55A4BD1BFFE83B9C80D052B985D182EA0A",
+    "DB2A3B7313D3FE14C8484B1E052588B9B7D2BBD2DF016199ECD06E1557CD0915",
+    "B3353BBB64E0EC377FD028370DF92B52C7891428CDC67EB6184B523D1DB246C3",
+    "2F63078490F00EF8D647D148D47954515E2327CFEF98C582664B4C0F6CC41659"
+);
+
+// RFC 7919, appendix A: ffdhe2048 safe prime, generator 2, q = (p - 1) / 2
+const FFDHE2048_P: &str = concat!(
+    "FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695",
+    "A9E13641146433FBCC939DCE249B3EF97D2FE363630C75D8F681B202AEC4617A",
+    "D3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935",
+    "984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797A",
+    "BC0AB182B324FB61D108A94BB2C8E3FBB96ADAB760D7F4681D4F42A3DE394DF4",
+    "AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61",
+    "9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005",
+    "C58EF1837D1683B2C6F34A26C1B2EFFA886B423861285C97FFFFFFFFFFFFFFFF"
+);
+
+// RFC 7919, appendix A: ffdhe3072 safe prime, generator 2, q = (p - 1) / 2
+const FFDHE3072_P: &str = concat!(
+    "FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695",
+    "A9E13641146433FBCC939DCE249B3EF97D2FE363630C75D8F681B202AEC4617A",
+    "D3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935",
+    "984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797A",
+    "BC0AB182B324FB61D108A94BB2C8E3FBB96ADAB760D7F4681D4F42A3DE394DF4",
+    "AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61",
+    "9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005",
+    "C58EF1837D1683B2C6F34A26C1B2EFFA886B4238611FCFDCDE355B3B6519035B",
+    "BC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C",
+    "AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF",
+    "5CAE82AB9C9DF69EE86D2BC522363A0DABC521979B0DEADA1DBF9A42D5C4484E",
+    "0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B66C62E37FFFFFFFFFFFFFFFF"
+);
+
+// RFC 7919, appendix A: ffdhe4096 safe prime, generator 2, q = (p - 1) / 2
+const FFDHE4096_P: &str = concat!(
+    "FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695",
+    "A9E13641146433FBCC939DCE249B3EF97D2FE363630C75D8F681B202AEC4617A",
+    "D3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935",
+    "984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797A",
+    "BC0AB182B324FB61D108A94BB2C8E3FBB96ADAB760D7F4681D4F42A3DE394DF4",
+    "AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61",
+    "9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005",
+    "C58EF1837D1683B2C6F34A26C1B2EFFA886B4238611FCFDCDE355B3B6519035B",
+    "BC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C",
+    "AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF",
+    "5CAE82AB9C9DF69EE86D2BC522363A0DABC521979B0DEADA1DBF9A42D5C4484E",
+    "0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B669E1EF16E6F52C3164DF4FB",
+    "7930E9E4E58857B6AC7D5F42D69F6D187763CF1D5503400487F55BA57E31CC7A",
+    "7135C886EFB4318AED6A1E012D9E6832A907600A918130C46DC778F971AD0038",
+    "092999A333CB8B7A1A1DB93D7140003C2A4ECEA9F98D0ACC0A8291CDCEC97DCF",
+    "8EC9B55A7F88A46B4DB5A851F44182E1C68A007E5E655F6AFFFFFFFFFFFFFFFF"
+);
+
+// RFC 7919, appendix A: ffdhe6144 safe prime, generator 2, q = (p - 1) / 2
+const FFDHE6144_P: &str = concat!(
+    "FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695",
+    "A9E13641146433FBCC939DCE249B3EF97D2FE363630C75D8F681B202AEC4617A",
+    "D3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935",
+    "984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797A",
+    "BC0AB182B324FB61D108A94BB2C8E3FBB96ADAB760D7F4681D4F42A3DE394DF4",
+    "AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61",
+    "9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005",
+    "C58EF1837D1683B2C6F34A26C1B2EFFA886B4238611FCFDCDE355B3B6519035B",
+    "BC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C",
+    "AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF",
+    "5CAE82AB9C9DF69EE86D2BC522363A0DABC521979B0DEADA1DBF9A42D5C4484E",
+    "0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B669E1EF16E6F52C3164DF4FB",
+    "7930E9E4E58857B6AC7D5F42D69F6D187763CF1D5503400487F55BA57E31CC7A",
+    "7135C886EFB4318AED6A1E012D9E6832A907600A918130C46DC778F971AD0038",
+    "092999A333CB8B7A1A1DB93D7140003C2A4ECEA9F98D0ACC0A8291CDCEC97DCF",
+    "8EC9B55A7F88A46B4DB5A851F44182E1C68A007E5E0DD9020BFD64B645036C7A",
+    "4E677D2C38532A3A23BA4442CAF53EA63BB454329B7624C8917BDD64B1C0FD4C",
+    "B38E8C334C701C3ACDAD0657FCCFEC719B1F5C3E4E46041F388147FB4CFDB477",
+    "A52471F7A9A96910B855322EDB6340D8A00EF092350511E30ABEC1FFF9E3A26E",
+    "7FB29F8C183023C3587E38DA0077D9B4763E4E4B94B2BBC194C6651E77CAF992",
+    "EEAAC0232A281BF6B3A739C1226116820AE8DB5847A67CBEF9C9091B462D538C",
+    "D72B03746AE77F5E62292C311562A846505DC82DB854338AE49F5235C95B9117",
+    "8CCF2DD5CACEF403EC9D1810C6272B045B3B71F9DC6B80D63FDD4A8E9ADB1E69",
+    "62A69526D43161C1A41D570D7938DAD4A40E329CD0E40E65FFFFFFFFFFFFFFFF"
+);
+
+// RFC 7919, appendix A: ffdhe8192 safe prime, generator 2, q = (p - 1) / 2
+const FFDHE8192_P: &str = concat!(
+    "FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695",
+    "A9E13641146433FBCC939DCE249B3EF97D2FE363630C75D8F681B202AEC4617A",
+    "D3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935",
+    "984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797A",
+    "BC0AB182B324FB61D108A94BB2C8E3FBB96ADAB760D7F4681D4F42A3DE394DF4",
+    "AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61",
+    "9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005",
+    "C58EF1837D1683B2C6F34A26C1B2EFFA886B4238611FCFDCDE355B3B6519035B",
+    "BC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C",
+    "AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF",
+    "5CAE82AB9C9DF69EE86D2BC522363A0DABC521979B0DEADA1DBF9A42D5C4484E",
+    "0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B669E1EF16E6F52C3164DF4FB",
+    "7930E9E4E58857B6AC7D5F42D69F6D187763CF1D5503400487F55BA57E31CC7A",
+    "7135C886EFB4318AED6A1E012D9E6832A907600A918130C46DC778F971AD0038",
+    "092999A333CB8B7A1A1DB93D7140003C2A4ECEA9F98D0ACC0A8291CDCEC97DCF",
+    "8EC9B55A7F88A46B4DB5A851F44182E1C68A007E5E0DD9020BFD64B645036C7A",
+    "4E677D2C38532A3A23BA4442CAF53EA63BB454329B7624C8917BDD64B1C0FD4C",
+    "B38E8C334C701C3ACDAD0657FCCFEC719B1F5C3E4E46041F388147FB4CFDB477",
+    "A52471F7A9A96910B855322EDB6340D8A00EF092350511E30ABEC1FFF9E3A26E",
+    "7FB29F8C183023C3587E38DA0077D9B4763E4E4B94B2BBC194C6651E77CAF992",
+    "EEAAC0232A281BF6B3A739C1226116820AE8DB5847A67CBEF9C9091B462D538C",
+    "D72B03746AE77F5E62292C311562A846505DC82DB854338AE49F5235C95B9117",
+    "8CCF2DD5CACEF403EC9D1810C6272B045B3B71F9DC6B80D63FDD4A8E9ADB1E69",
+    "62A69526D43161C1A41D570D7938DAD4A40E329CCFF46AAA36AD004CF600C838",
+    "1E425A31D951AE64FDB23FCEC9509D43687FEB69EDD1CC5E0B8CC3BDF64B10EF",
+    "86B63142A3AB8829555B2F747C932665CB2C0F1CC01BD70229388839D2AF05E4",
+    "54504AC78B7582822846C0BA35C35F5C59160CC046FD8251541FC68C9C86B022",
+    "BB7099876A460E7451A8A93109703FEE1C217E6C3826E52C51AA691E0E423CFC",
+    "99E9E31650C1217B624816CDAD9A95F9D5B8019488D9C0A0A1FE3075A577E231",
+    "83F81D4A3F2FA4571EFC8CE0BA8A4FE8B6855DFE72B0A66EDED2FBABFBE58A30",
+    "FAFABE1C5D71A87E2F741EF8C1FE86FEA6BBFDE530677F0D97D11D49F7A8443D",
+    "0822E506A9F4614E011E2A94838FF88CD68C8BB7C5C6424CFFFFFFFFFFFFFFFF"
+);
+
+/// Named standard parameter sets.
+///
+/// Every set has a stable identifier (`id`) that clients and servers can
+/// exchange and store. The mod p sets use the group's published generator as
+/// alpha and derive beta from `BETA_SEED` with `derive_generator`.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamSet {
+    Rfc5114_1024_160,
+    Rfc5114_2048_224,
+    Rfc5114_2048_256,
+    Ffdhe2048,
+    Ffdhe3072,
+    Ffdhe4096,
+    Ffdhe6144,
+    Ffdhe8192,
+    #[cfg(feature = "ristretto")]
+    Ristretto255,
+    #[cfg(feature = "p256")]
+    P256,
+    #[cfg(feature = "secp256k1")]
+    Secp256k1,
+}
+
+impl ParamSet {
+    pub const ALL: &'static [ParamSet] = &[
+        ParamSet::Rfc5114_1024_160,
+        ParamSet::Rfc5114_2048_224,
+        ParamSet::Rfc5114_2048_256,
+        ParamSet::Ffdhe2048,
+        ParamSet::Ffdhe3072,
+        ParamSet::Ffdhe4096,
+        ParamSet::Ffdhe6144,
+        ParamSet::Ffdhe8192,
+        #[cfg(feature = "ristretto")]
+        ParamSet::Ristretto255,
+        #[cfg(feature = "p256")]
+        ParamSet::P256,
+        #[cfg(feature = "secp256k1")]
+        ParamSet::Secp256k1,
+    ];
+
+    // Stable identifier, never change these once released
+    pub fn id(&self) -> &'static str {
+        match self {
+            ParamSet::Rfc5114_1024_160 => "rfc5114-1024-160",
+            ParamSet::Rfc5114_2048_224 => "rfc5114-2048-224",
+            ParamSet::Rfc5114_2048_256 => "rfc5114-2048-256",
+            ParamSet::Ffdhe2048 => "ffdhe2048",
+            ParamSet::Ffdhe3072 => "ffdhe3072",
+            ParamSet::Ffdhe4096 => "ffdhe4096",
+            ParamSet::Ffdhe6144 => "ffdhe6144",
+            ParamSet::Ffdhe8192 => "ffdhe8192",
+            #[cfg(feature = "ristretto")]
+            ParamSet::Ristretto255 => "ristretto255",
+            #[cfg(feature = "p256")]
+            ParamSet::P256 => "p256",
+            #[cfg(feature = "secp256k1")]
+            ParamSet::Secp256k1 => "secp256k1",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ParamSet> {
+        ParamSet::ALL.iter().copied().find(|set| set.id() == id)
+    }
+
+    // ZKP instance for the mod p sets, None for the curve sets
+    pub fn zkp(&self) -> Option<ZKP> {
+        let (p, q, alpha) = match self {
+            ParamSet::Rfc5114_1024_160 => (RFC5114_1024_P, RFC5114_1024_Q, RFC5114_1024_G),
+            ParamSet::Rfc5114_2048_224 => {
+                (RFC5114_2048_224_P, RFC5114_2048_224_Q, RFC5114_2048_224_G)
+            }
+            ParamSet::Rfc5114_2048_256 => {
+                (RFC5114_2048_256_P, RFC5114_2048_256_Q, RFC5114_2048_256_G)
+            }
+            ParamSet::Ffdhe2048 => return Some(ffdhe(FFDHE2048_P)),
+            ParamSet::Ffdhe3072 => return Some(ffdhe(FFDHE3072_P)),
+            ParamSet::Ffdhe4096 => return Some(ffdhe(FFDHE4096_P)),
+            ParamSet::Ffdhe6144 => return Some(ffdhe(FFDHE6144_P)),
+            ParamSet::Ffdhe8192 => return Some(ffdhe(FFDHE8192_P)),
+            #[cfg(any(feature = "ristretto", feature = "p256", feature = "secp256k1"))]
+            _ => return None,
+        };
+        Some(modp(
+            hex_to_biguint(p),
+            hex_to_biguint(q),
+            hex_to_biguint(alpha),
+        ))
+    }
+
+    // Protocol instance for any set, for callers that pick the set at
+    // runtime. Built on first use and shared after, so the verifier states
+    // from `challenge_encoded` can outlive the call
+    pub fn protocol(&self) -> &'static dyn DynChaumPedersen {
+        static PROTOCOLS: [OnceLock<Box<dyn DynChaumPedersen>>; ParamSet::ALL.len()] =
+            [const { OnceLock::new() }; ParamSet::ALL.len()];
+        let index = ParamSet::ALL
+            .iter()
+            .position(|set| set == self)
+            .expect("every set is in ALL");
+        PROTOCOLS[index]
+            .get_or_init(|| self.build_protocol())
+            .as_ref()
+    }
+
+    fn build_protocol(&self) -> Box<dyn DynChaumPedersen> {
+        match self {
+            #[cfg(feature = "ristretto")]
+            ParamSet::Ristretto255 => Box::new(crate::RistrettoZKP::ristretto255()),
+            #[cfg(feature = "p256")]
+            ParamSet::P256 => Box::new(crate::P256ZKP::p256()),
+            #[cfg(feature = "secp256k1")]
+            ParamSet::Secp256k1 => Box::new(crate::Secp256k1ZKP::secp256k1()),
+            _ => Box::new(self.zkp().expect("every other set is a mod p group")),
+        }
+    }
+}
+
+impl fmt::Display for ParamSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for ParamSet {
+    type Err = String;
+
+    fn from_str(id: &str) -> Result<ParamSet, String> {
+        ParamSet::from_id(id).ok_or_else(|| format!("unknown parameter set {}", id))
+    }
+}
+
+fn hex_to_biguint(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).expect("could not convert parameter from hex")
+}
+
+// Challenges are drawn below 2^128 in every mod p set
+fn challenge_bound() -> BigUint {
+    BigUint::new(vec![u32::MAX; 4])
+}
+
+fn modp(p: BigUint, q: BigUint, alpha: BigUint) -> ZKP {
+    let beta =
+        derive_generator(&p, &q, BETA_SEED, BETA_INDEX).expect("could not derive beta from seed");
+    ZKP::new(alpha, beta, p, q, challenge_bound())
+}
+
+// The RFC 7919 groups are safe-prime groups generated by 2
+fn ffdhe(p: &str) -> ZKP {
+    let p = hex_to_biguint(p);
+    let q = (&p - 1u32) >> 1;
+    modp(p, q, BigUint::from(2u32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::modp::ValidationPolicy;
+    use crate::protocol::Sigma;
+    use crate::{Group, Protocol};
+
+    #[test]
+    fn test_ids_round_trip() {
+        for set in ParamSet::ALL {
+            assert_eq!(ParamSet::from_id(set.id()), Some(*set));
+            assert_eq!(set.to_string().parse::<ParamSet>(), Ok(*set));
+        }
+        let mut ids: Vec<_> = ParamSet::ALL.iter().map(ParamSet::id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), ParamSet::ALL.len());
+        assert!(ParamSet::from_id("modp42").is_none());
+    }
+
+    #[test]
+    fn test_1024_matches_default_config() {
+        let (alpha, beta, p, q, challenge_bound) = ZKP::get_1024_bits_config().unwrap();
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        assert_eq!(zkp.alpha, alpha);
+        assert_eq!(zkp.beta, beta);
+        assert_eq!(zkp.group.p, p);
+        assert_eq!(zkp.group.q, q);
+        assert_eq!(zkp.group.challenge_bound, challenge_bound);
+    }
+
+    #[test]
+    fn test_rfc5114_sets_are_valid() {
+        for set in [
+            ParamSet::Rfc5114_1024_160,
+            ParamSet::Rfc5114_2048_224,
+            ParamSet::Rfc5114_2048_256,
+        ] {
+            let zkp = set.zkp().unwrap();
+            assert_eq!(zkp.validate(), Ok(()), "{}", set);
+        }
+    }
+
+    #[test]
+    fn test_ffdhe_sets_are_valid() {
+        // a couple of rounds is enough to catch a typo in the constants and
+        // keeps the 8192-bit group affordable in debug builds
+        let policy = ValidationPolicy {
+            prime_rounds: 2,
+            ..ValidationPolicy::default()
+        };
+        for set in [
+            ParamSet::Ffdhe2048,
+            ParamSet::Ffdhe3072,
+            ParamSet::Ffdhe4096,
+        ] {
+            let zkp = set.zkp().unwrap();
+            assert_eq!(zkp.validate_with(&policy), Ok(()), "{}", set);
+        }
+        for (set, bits) in [(ParamSet::Ffdhe6144, 6144), (ParamSet::Ffdhe8192, 8192)] {
+            let zkp = set.zkp().unwrap();
+            assert_eq!(zkp.group.p.bits(), bits);
+            assert_eq!(zkp.group.q.bits(), bits - 1);
+        }
+    }
+
+    #[test]
+    fn test_protocol_for_every_set() {
+        for set in ParamSet::ALL {
+            let protocol = set.protocol();
+            assert!(!protocol.random_challenge().is_empty(), "{}", set);
+            assert!(std::ptr::addr_eq(protocol, set.protocol()), "{}", set);
+        }
+
+        let zkp = ParamSet::Rfc5114_2048_256.zkp().unwrap();
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+        let c = zkp.generate_random();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+
+        let group = &zkp.group;
+        let verified = ParamSet::Rfc5114_2048_256.protocol().verify_encoded(
+            &group.element_to_bytes(y1.element()),
+            &group.element_to_bytes(y2.element()),
+            &group.element_to_bytes(r1.element()),
+            &group.element_to_bytes(r2.element()),
+            &group.scalar_to_bytes(&s),
+            &group.scalar_to_bytes(&c),
+        );
+        assert_eq!(verified, Ok(()));
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{CryptoRng, RngCore};
+
+use zeroize::Zeroize;
+
+use crate::group::Group;
+use crate::secret::SecretKey;
+
+// Salt length `random_salt` produces and `derive_secret` insists on
+pub const SALT_LEN: usize = argon2::RECOMMENDED_SALT_LEN;
+
+/// Argon2id cost parameters for turning a password into the secret x.
+///
+/// The defaults are the argon2 crate's (19 MiB, 2 passes, 1 lane), which
+/// follow the OWASP recommendation for interactive logins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordKdf {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KdfError {
+    // the per-user salt is shorter than SALT_LEN
+    SaltTooShort { len: usize, min_len: usize },
+    // argon2 rejected the cost parameters
+    InvalidCost(argon2::Error),
+}
+
+impl fmt::Display for KdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KdfError::SaltTooShort { len, min_len } => write!(
+                f,
+                "salt is {} bytes, at least {} are required",
+                len, min_len
+            ),
+            KdfError::InvalidCost(err) => write!(f, "invalid argon2 cost: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for KdfError {}
+
+impl Default for PasswordKdf {
+    fn default() -> Self {
+        PasswordKdf {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+// Fresh salt for a user, stored next to their registration
+pub fn random_salt() -> Vec<u8> {
+    random_salt_with_rng(&mut rand::thread_rng())
+}
+
+pub fn random_salt_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Vec<u8> {
+    let mut salt = vec![0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    salt
+}
+
+impl PasswordKdf {
+    /// Derive the secret x in Z_q from a password.
+    ///
+    /// The Argon2id salt is the parameter set id followed by the user's salt,
+    /// so the same password gives unrelated secrets in different groups. The
+    /// output is `group.uniform_bytes_len()` bytes long and reduced mod q.
+    pub fn derive_secret<G: Group>(
+        &self,
+        group: &G,
+        param_set: &str,
+        salt: &[u8],
+        password: &[u8],
+    ) -> Result<SecretKey<G>, KdfError> {
+        if salt.len() < SALT_LEN {
+            return Err(KdfError::SaltTooShort {
+                len: salt.len(),
+                min_len: SALT_LEN,
+            });
+        }
+        let len = group.uniform_bytes_len();
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(len),
+        )
+        .map_err(KdfError::InvalidCost)?;
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+        let mut full_salt = (param_set.len() as u64).to_be_bytes().to_vec();
+        full_salt.extend_from_slice(param_set.as_bytes());
+        full_salt.extend_from_slice(salt);
+
+        let mut okm = vec![0u8; len];
+        argon2
+            .hash_password_into(password, &full_salt, &mut okm)
+            .map_err(KdfError::InvalidCost)?;
+        let x = SecretKey::new(group.scalar_from_uniform_bytes(&okm));
+        okm.zeroize();
+        Ok(x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Sigma;
+    use crate::{ParamSet, Protocol};
+
+    // Cheapest cost argon2 accepts, the tests only check the plumbing
+    const TEST_KDF: PasswordKdf = PasswordKdf {
+        memory_kib: 8,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_derive_secret_modp() {
+        let set = ParamSet::Rfc5114_1024_160;
+        let zkp = set.zkp().unwrap();
+        let salt = random_salt();
+
+        let x = TEST_KDF
+            .derive_secret(&zkp.group, set.id(), &salt, b"hunter2")
+            .unwrap();
+        assert!(x.expose().value() < zkp.group.q);
+
+        // password, salt, parameter set and cost all change the secret
+        let other = |kdf: PasswordKdf, id: &str, salt: &[u8], password: &[u8]| {
+            let x = kdf.derive_secret(&zkp.group, id, salt, password).unwrap();
+            x.expose().clone()
+        };
+        assert_eq!(other(TEST_KDF, set.id(), &salt, b"hunter2"), *x.expose());
+        assert_ne!(other(TEST_KDF, set.id(), &salt, b"hunter3"), *x.expose());
+        assert_ne!(
+            other(TEST_KDF, set.id(), &random_salt(), b"hunter2"),
+            *x.expose()
+        );
+        assert_ne!(other(TEST_KDF, "ffdhe2048", &salt, b"hunter2"), *x.expose());
+        let slower = PasswordKdf {
+            iterations: 2,
+            ..TEST_KDF
+        };
+        assert_ne!(other(slower, set.id(), &salt, b"hunter2"), *x.expose());
+
+        // and the derived secret runs through the protocol
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+        let c = zkp.generate_random();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_derive_secret_ristretto() {
+        let zkp = crate::RistrettoZKP::ristretto255();
+        let x = TEST_KDF
+            .derive_secret(&zkp.group, "ristretto255", &random_salt(), b"hunter2")
+            .unwrap();
+        assert_ne!(*x.expose(), curve25519_dalek::Scalar::ZERO);
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_derive_secret_p256() {
+        let zkp = crate::P256ZKP::p256();
+        assert!(TEST_KDF
+            .derive_secret(&zkp.group, "p256", &random_salt(), b"hunter2")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_errors() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        assert_eq!(
+            TEST_KDF
+                .derive_secret(&zkp.group, "rfc5114-1024-160", &[0u8; 8], b"pw")
+                .err(),
+            Some(KdfError::SaltTooShort {
+                len: 8,
+                min_len: SALT_LEN
+            })
+        );
+        let no_memory = PasswordKdf {
+            memory_kib: 0,
+            ..TEST_KDF
+        };
+        assert!(matches!(
+            no_memory.derive_secret(&zkp.group, "rfc5114-1024-160", &random_salt(), b"pw"),
+            Err(KdfError::InvalidCost(_))
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::fiat_shamir::FiatShamir;
+use crate::group::Group;
+use crate::nonce::nonce_with_mode;
+use crate::{GroupElement, Nonce, NonceMode, SecretKey, ZkpError};
+
+/// A proof system over the group G.
+///
+/// Every protocol in the crate proves knowledge of one secret x that is the
+/// log of a public value h_i = g_i^x to each of its bases g_i: the prover
+/// commits to r_i = g_i^k and answers a challenge c with s = k - c * x mod
+/// q, and the verifier checks r_i = g_i^s * h_i^c. Checking elements,
+/// drawing challenges, keys and nonces, hashing the statement and running
+/// either side only depend on that, so every protocol takes them from here
+/// and only says where its group and bases are and how its messages look.
+pub trait Protocol<G: Group> {
+    // Label every transcript of the protocol starts with. Derived nonces
+    // hash the statement under it followed by " nonce"
+    const LABEL: &'static [u8];
+
+    // The public values h_i in the shape the protocol hands them out, over
+    // elements as computed or as checked on receipt
+    type PublicKey<T>;
+
+    // The prover's first message, the r_i in the protocol's shape
+    type Commitment;
+
+    fn group(&self) -> &G;
+
+    fn bases(&self) -> Vec<&G::Element>;
+
+    // Put one value per base into the protocol's shape. Panics if the count
+    // does not fit it
+    fn public_key_from<T>(h: Vec<T>) -> Self::PublicKey<T>;
+
+    fn public_key_values<T>(key: Self::PublicKey<T>) -> Vec<T>;
+
+    // The commitment to one r_i per base, as `public_key_from`
+    fn commitment_from(r: Vec<G::Element>) -> Self::Commitment;
+
+    // Check a received commitment, one r_i per base
+    fn check_commitment(
+        &self,
+        commitment: Self::Commitment,
+    ) -> Result<Vec<GroupElement<G>>, ZkpError>;
+
+    // The statement every hash starts with: the group, the bases and the
+    // public values
+    fn statement(&self, label: &[u8], h: &[&G::Element]) -> FiatShamir {
+        let group = self.group();
+        let mut transcript = FiatShamir::new(label);
+        transcript.append(&group.params_bytes());
+        for element in self.bases().into_iter().chain(h.iter().copied()) {
+            transcript.append_element(group, element);
+        }
+        transcript
+    }
+
+    // Check an element received from the other party, naming it in the
+    // error, see `GroupElement::new`
+    fn check_element(
+        &self,
+        name: &'static str,
+        element: G::Element,
+    ) -> Result<GroupElement<G>, ZkpError> {
+        GroupElement::new(self.group(), name, element)
+    }
+
+    // Generate a random scalar for challenges
+    fn generate_random(&self) -> G::Scalar {
+        self.generate_random_with_rng(&mut rand::thread_rng())
+    }
+
+    fn generate_random_with_rng<R: RngCore + CryptoRng>(&self, rng: &mut R) -> G::Scalar {
+        self.group().random_challenge(rng)
+    }
+
+    fn generate_secret_key(&self) -> SecretKey<G> {
+        self.generate_secret_key_with_rng(&mut rand::thread_rng())
+    }
+
+    fn generate_secret_key_with_rng<R: RngCore + CryptoRng>(&self, rng: &mut R) -> SecretKey<G> {
+        SecretKey::random(self.group(), rng)
+    }
+}
+
+/// The steps of a `Protocol` that touch the secret or the nonce, or only
+/// see the messages as one value per base.
+///
+/// Implemented for every protocol, and crate-private so that nonces are
+/// only ever used through the provers.
+pub(crate) trait Sigma<G: Group>: Protocol<G> {
+    // g_i^e for every base, in constant time in e
+    fn exp_bases(&self, e: &G::Scalar) -> Vec<G::Element> {
+        let group = self.group();
+        self.bases()
+            .into_iter()
+            .map(|g| group.exp_secret(g, e))
+            .collect()
+    }
+
+    // Solve s = k - c * x mod q, in constant time in k and x
+    fn solve(&self, k: &Nonce<G>, c: &G::Scalar, x: &SecretKey<G>) -> G::Scalar {
+        self.group().sub_mul_secret(k.expose(), c, x.expose())
+    }
+
+    // Nonce for a commitment by the prover holding x, drawn as the mode
+    // says. Derived nonces are bound to the statement and the context
+    fn nonce_with_mode<R: RngCore + CryptoRng>(
+        &self,
+        mode: NonceMode,
+        x: &SecretKey<G>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> Nonce<G> {
+        let statement = || {
+            let h = self.exp_bases(x.expose());
+            let label = [Self::LABEL, b" nonce"].concat();
+            let mut statement = self.statement(&label, &h.iter().collect::<Vec<_>>());
+            statement.append(context);
+            statement
+        };
+        nonce_with_mode(self.group(), mode, x, statement, rng)
+    }
+
+    // c = H(statement, r_1..r_n, context)
+    fn challenge_values(&self, h: &[&G::Element], r: &[&G::Element], context: &[u8]) -> G::Scalar {
+        let group = self.group();
+        let mut transcript = self.statement(Self::LABEL, h);
+        for element in r {
+            transcript.append_element(group, element);
+        }
+        transcript.append(context);
+        transcript.challenge(group)
+    }
+
+    // verify that r_i = g_i^s * h_i^c for every base g_i, each right-hand
+    // side a single two-base multi-exponentiation on public values
+    fn verify_values(
+        &self,
+        h: &[&GroupElement<G>],
+        r: &[&GroupElement<G>],
+        s: &G::Scalar,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        let group = self.group();
+        let bases = self.bases();
+        if h.len() != bases.len() {
+            return Err(ZkpError::LengthMismatch("h"));
+        }
+        if r.len() != bases.len() {
+            return Err(ZkpError::LengthMismatch("r"));
+        }
+        if !group.scalar_in_range(s) {
+            return Err(ZkpError::ScalarOutOfRange("s"));
+        }
+        let exponents = [s.clone(), c.clone()];
+        let verified = bases.into_iter().zip(h).zip(r).all(|((g, h), r)| {
+            *r.element() == group.multi_exp(&[g.clone(), h.element().clone()], &exponents)
+        });
+        if verified {
+            Ok(())
+        } else {
+            Err(ZkpError::VerificationFailed)
+        }
+    }
+
+    // The commitments and the response of a proof in one message, with the
+    // challenge hashed from them
+    fn prove_values<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        mode: NonceMode,
+        rng: &mut R,
+    ) -> (Vec<G::Element>, G::Scalar) {
+        let h = self.exp_bases(x.expose());
+        let k = self.nonce_with_mode(mode, x, context, rng);
+        let r = self.exp_bases(k.expose());
+        let c = self.challenge_values(
+            &h.iter().collect::<Vec<_>>(),
+            &r.iter().collect::<Vec<_>>(),
+            context,
+        );
+        let s = self.solve(&k, &c, x);
+        (r, s)
+    }
+
+    // Check a proof from `prove_values`, with its commitments checked like
+    // every other element from the prover
+    fn verify_proof(
+        &self,
+        h: &[&GroupElement<G>],
+        commitment: Self::Commitment,
+        s: &G::Scalar,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        let r = self.check_commitment(commitment)?;
+        let r: Vec<&GroupElement<G>> = r.iter().collect();
+        let h_elements: Vec<&G::Element> = h.iter().map(|h| h.element()).collect();
+        let r_elements: Vec<&G::Element> = r.iter().map(|r| r.element()).collect();
+        let c = self.challenge_values(&h_elements, &r_elements, context);
+        self.verify_values(h, &r, s, &c)
+    }
+}
+
+impl<G: Group, P: Protocol<G>> Sigma<G> for P {}
@@ -0,0 +1,193 @@
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{Identity, VartimeMultiscalarMul};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha512;
+use zeroize::Zeroize;
+
+use crate::group::Group;
+use crate::ChaumPedersen;
+
+// Domain separator hashed to the curve to get the second generator
+const BETA_SEED: &[u8] = b"zkp-chaum-pedersen ristretto255 beta";
+
+/// Ristretto255 prime-order group: 32-byte elements and 32-byte scalars.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ristretto255;
+
+// ZKP over Ristretto255
+pub type RistrettoZKP = ChaumPedersen<Ristretto255>;
+
+impl Group for Ristretto255 {
+    type Element = RistrettoPoint;
+    type Scalar = Scalar;
+
+    fn identity(&self) -> RistrettoPoint {
+        RistrettoPoint::identity()
+    }
+
+    fn exp(&self, base: &RistrettoPoint, exponent: &Scalar) -> RistrettoPoint {
+        base * exponent
+    }
+
+    fn op(&self, a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a + b
+    }
+
+    fn multi_exp(&self, bases: &[RistrettoPoint], exponents: &[Scalar]) -> RistrettoPoint {
+        RistrettoPoint::vartime_multiscalar_mul(exponents, bases)
+    }
+
+    fn scalar_add(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a + b
+    }
+
+    fn scalar_sub(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a - b
+    }
+
+    fn scalar_mul(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a * b
+    }
+
+    fn zeroize_scalar(scalar: &mut Scalar) {
+        scalar.zeroize();
+    }
+
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar {
+        Scalar::random(rng)
+    }
+
+    fn hash_to_scalar(&self, digest: &[u8]) -> Scalar {
+        Scalar::hash_from_bytes::<Sha512>(digest)
+    }
+
+    fn uniform_bytes_len(&self) -> usize {
+        64
+    }
+
+    fn scalar_from_uniform_bytes(&self, bytes: &[u8]) -> Scalar {
+        let wide: [u8; 64] = bytes
+            .try_into()
+            .expect("ristretto255 reduces exactly 64 bytes");
+        Scalar::from_bytes_mod_order_wide(&wide)
+    }
+
+    fn params_bytes(&self) -> Vec<u8> {
+        b"ristretto255".to_vec()
+    }
+
+    fn element_to_bytes(&self, element: &RistrettoPoint) -> Vec<u8> {
+        element.compress().to_bytes().to_vec()
+    }
+
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<RistrettoPoint> {
+        CompressedRistretto::from_slice(bytes).ok()?.decompress()
+    }
+
+    fn scalar_to_bytes(&self, scalar: &Scalar) -> Vec<u8> {
+        scalar.to_bytes().to_vec()
+    }
+
+    fn scalar_from_bytes(&self, bytes: &[u8]) -> Option<Scalar> {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Scalar::from_canonical_bytes(bytes).into()
+    }
+}
+
+impl RistrettoZKP {
+    // alpha is the standard basepoint, beta is hashed to the curve so that
+    // nobody knows log_alpha(beta)
+    pub fn ristretto255() -> RistrettoZKP {
+        let beta = RistrettoPoint::hash_from_bytes::<Sha512>(BETA_SEED);
+        ChaumPedersen::from_group(Ristretto255, RISTRETTO_BASEPOINT_POINT, beta)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::Sigma;
+    use crate::{Nonce, Protocol, SecretKey, ZkpError};
+
+    #[test]
+    fn test_toy_example() {
+        let zkp = RistrettoZKP::ristretto255();
+
+        let x = SecretKey::new(Scalar::from(6u32));
+        let k = Nonce::new(Scalar::from(7u32));
+
+        let c = Scalar::from(4u32);
+
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+        assert_eq!(s, Scalar::from(7u32) - Scalar::from(24u32));
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+
+        //  fake secret
+        let x_fake = SecretKey::new(Scalar::from(7u32));
+        let s_fake = zkp.solve(&k, &c, &x_fake);
+
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_random_secret() {
+        let zkp = RistrettoZKP::ristretto255();
+
+        let x = zkp.generate_secret_key();
+        let k = zkp.generate_nonce(&mut rand::thread_rng());
+
+        let c = zkp.generate_random();
+
+        let (y1, y2) = zkp.exponentiate(&x);
+        let (r1, r2) = zkp.commit(&k);
+        let [y1, y2, r1, r2] = [y1, y2, r1, r2].map(|e| zkp.check_element("e", e).unwrap());
+        let s = zkp.solve(&k, &c, &x);
+
+        assert_eq!(zkp.verify(&y1, &y2, &r1, &r2, &s, &c), Ok(()));
+
+        //  fake secret
+        let x_fake = zkp.generate_secret_key();
+        let s_fake = zkp.solve(&k, &c, &x_fake);
+
+        assert_eq!(
+            zkp.verify(&y1, &y2, &r1, &r2, &s_fake, &c),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_generators_are_independent() {
+        let zkp = RistrettoZKP::ristretto255();
+        assert_ne!(zkp.alpha, zkp.beta);
+        assert_ne!(zkp.beta, zkp.group.identity());
+    }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        let zkp = RistrettoZKP::ristretto255();
+        let group = &zkp.group;
+
+        let x = zkp.generate_random();
+        let y1 = group.exp(&zkp.alpha, &x);
+
+        let y1_bytes = group.element_to_bytes(&y1);
+        let x_bytes = group.scalar_to_bytes(&x);
+        assert_eq!(y1_bytes.len(), 32);
+        assert_eq!(x_bytes.len(), 32);
+        assert_eq!(group.element_from_bytes(&y1_bytes), Some(y1));
+        assert_eq!(group.scalar_from_bytes(&x_bytes), Some(x));
+
+        // not a valid point encoding, and a scalar >= l
+        assert_eq!(group.element_from_bytes(&[0xff; 32]), None);
+        assert_eq!(group.scalar_from_bytes(&[0xff; 32]), None);
+        assert_eq!(group.element_from_bytes(&y1_bytes[..31]), None);
+    }
+}
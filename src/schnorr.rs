@@ -0,0 +1,361 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::fiat_shamir::{decode_fields, encode_fields};
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::session::{AwaitingChallenge, AwaitingResponse, Prover, Verifier};
+use crate::{ChaumPedersen, GroupElement, NonceMode, Protocol, SecretKey, ZkpError};
+
+/// Schnorr proof of knowledge of x with y = alpha^x: Chaum-Pedersen without
+/// the second base beta.
+///
+/// It runs over the same groups, parameters, scalars and Fiat-Shamir
+/// transcript as `ChaumPedersen`, for deployments that only need to prove
+/// knowledge of x. The public key is one element instead of two, and
+/// verifying is one two-base multi-exponentiation instead of two.
+#[derive(Debug, Clone)]
+pub struct Schnorr<G: Group> {
+    pub group: G,
+    pub alpha: G::Element,
+}
+
+/// Non-interactive Schnorr proof: the commitment and the response.
+pub struct SchnorrProof<G: Group> {
+    pub r: G::Element,
+    pub s: G::Scalar,
+}
+
+impl<G: Group> Clone for SchnorrProof<G> {
+    fn clone(&self) -> Self {
+        SchnorrProof {
+            r: self.r.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: Group> PartialEq for SchnorrProof<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r == other.r && self.s == other.s
+    }
+}
+
+impl<G: Group> fmt::Debug for SchnorrProof<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchnorrProof")
+            .field("r", &self.r)
+            .field("s", &self.s)
+            .finish()
+    }
+}
+
+impl<G: Group> SchnorrProof<G> {
+    // Length-prefixed r || s, as for `Proof`
+    pub fn to_bytes(&self, group: &G) -> Vec<u8> {
+        encode_fields(&[
+            group.element_to_bytes(&self.r),
+            group.scalar_to_bytes(&self.s),
+        ])
+    }
+
+    pub fn from_bytes(group: &G, bytes: &[u8]) -> Result<SchnorrProof<G>, ZkpError> {
+        let fields = decode_fields(bytes, 2).ok_or(ZkpError::Decoding("proof"))?;
+        Ok(SchnorrProof {
+            r: group
+                .element_from_bytes(fields[0])
+                .ok_or(ZkpError::Decoding("r"))?,
+            s: group
+                .scalar_from_bytes(fields[1])
+                .ok_or(ZkpError::Decoding("s"))?,
+        })
+    }
+}
+
+impl<G: Group + Clone> ChaumPedersen<G> {
+    // Schnorr over the same group and alpha. A secret key gives the same
+    // y = y1 in both
+    pub fn schnorr(&self) -> Schnorr<G> {
+        Schnorr {
+            group: self.group.clone(),
+            alpha: self.alpha.clone(),
+        }
+    }
+}
+
+impl<G: Group> Protocol<G> for Schnorr<G> {
+    const LABEL: &'static [u8] = b"zkp-schnorr";
+
+    type PublicKey<T> = T;
+    type Commitment = G::Element;
+
+    fn group(&self) -> &G {
+        &self.group
+    }
+
+    fn bases(&self) -> Vec<&G::Element> {
+        vec![&self.alpha]
+    }
+
+    fn public_key_from<T>(h: Vec<T>) -> T {
+        let Ok([y]) = <[T; 1]>::try_from(h) else {
+            panic!("Schnorr has one base");
+        };
+        y
+    }
+
+    fn public_key_values<T>(y: T) -> Vec<T> {
+        vec![y]
+    }
+
+    fn commitment_from(r: Vec<G::Element>) -> G::Element {
+        Self::public_key_from(r)
+    }
+
+    fn check_commitment(&self, r: G::Element) -> Result<Vec<GroupElement<G>>, ZkpError> {
+        Ok(vec![self.check_element("r", r)?])
+    }
+}
+
+impl<G: Group> Schnorr<G> {
+    // Fails unless alpha is a non-identity element of the group
+    pub fn from_group(group: G, alpha: G::Element) -> Result<Self, ZkpError> {
+        GroupElement::new(&group, "alpha", alpha.clone())?;
+        Ok(Schnorr { group, alpha })
+    }
+
+    // Compute the public key y = alpha^x
+    pub fn public_key(&self, x: &SecretKey<G>) -> G::Element {
+        self.group.exp_secret(&self.alpha, x.expose())
+    }
+
+    /// verify that r = alpha^s * y^c
+    ///
+    /// The response s must be reduced mod q.
+    pub fn verify(
+        &self,
+        y: &GroupElement<G>,
+        r: &GroupElement<G>,
+        s: &G::Scalar,
+        c: &G::Scalar,
+    ) -> Result<(), ZkpError> {
+        self.verify_values(&[y], &[r], s, c)
+    }
+
+    // c = H(group, alpha, y, r, context)
+    pub fn challenge(&self, y: &G::Element, r: &G::Element, context: &[u8]) -> G::Scalar {
+        self.challenge_values(&[y], &[r], context)
+    }
+
+    // Prove knowledge of x with y = alpha^x in one message
+    pub fn prove_non_interactive(&self, x: &SecretKey<G>, context: &[u8]) -> SchnorrProof<G> {
+        self.prove_non_interactive_with_rng(x, context, &mut rand::thread_rng())
+    }
+
+    pub fn prove_non_interactive_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        rng: &mut R,
+    ) -> SchnorrProof<G> {
+        self.prove_non_interactive_with_mode(x, context, NonceMode::Random, rng)
+    }
+
+    pub fn prove_non_interactive_with_mode<R: RngCore + CryptoRng>(
+        &self,
+        x: &SecretKey<G>,
+        context: &[u8],
+        mode: NonceMode,
+        rng: &mut R,
+    ) -> SchnorrProof<G> {
+        let (r, s) = self.prove_values(x, context, mode, rng);
+        SchnorrProof {
+            r: Self::commitment_from(r),
+            s,
+        }
+    }
+
+    pub fn verify_non_interactive(
+        &self,
+        y: &GroupElement<G>,
+        proof: &SchnorrProof<G>,
+        context: &[u8],
+    ) -> Result<(), ZkpError> {
+        self.verify_proof(&[y], proof.r.clone(), &proof.s, context)
+    }
+}
+
+/// Prover side of one interactive Schnorr identification: `Prover` run
+/// over `Schnorr`, committing to r and answering with s.
+pub type SchnorrProver<'a, G> = Prover<'a, G, Schnorr<G>>;
+
+/// A Schnorr prover that sent its commitment r and waits for the challenge.
+pub type SchnorrAwaitingChallenge<'a, G> = AwaitingChallenge<'a, G, Schnorr<G>>;
+
+/// Verifier side of one interactive Schnorr identification against a
+/// registered y.
+pub type SchnorrVerifier<'a, G> = Verifier<'a, G, Schnorr<G>>;
+
+/// A Schnorr verifier that issued its challenge and waits for s.
+pub type SchnorrAwaitingResponse<'a, G> = AwaitingResponse<'a, G, Schnorr<G>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::modp::Scalar;
+    use crate::ParamSet;
+    use num_bigint::BigUint;
+
+    // Interactive runs by the holder of x and by an impostor, and one
+    // non-interactive run, to cover every group
+    fn run<G: Group>(schnorr: &Schnorr<G>) {
+        let prover = SchnorrProver::new(schnorr, schnorr.generate_secret_key());
+        let y = schnorr.check_element("y", prover.public_key()).unwrap();
+        let identify = |prover: SchnorrProver<'_, G>| {
+            let verifier = SchnorrVerifier::new(schnorr, y.clone());
+            let (r, prover) = prover.commit();
+            let (c, verifier) = verifier.challenge(r).unwrap();
+            verifier.verify(&prover.respond(&c))
+        };
+        assert_eq!(identify(prover), Ok(()));
+        let impostor = SchnorrProver::new(schnorr, schnorr.generate_secret_key());
+        assert_eq!(identify(impostor), Err(ZkpError::VerificationFailed));
+
+        let x = schnorr.generate_secret_key();
+        let y = schnorr.check_element("y", schnorr.public_key(&x)).unwrap();
+        let proof = schnorr.prove_non_interactive(&x, b"alice");
+        assert_eq!(schnorr.verify_non_interactive(&y, &proof, b"alice"), Ok(()));
+    }
+
+    #[test]
+    fn test_schnorr_modp() {
+        run(&ParamSet::Rfc5114_1024_160.zkp().unwrap().schnorr());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_schnorr_ristretto() {
+        run(&crate::RistrettoZKP::ristretto255().schnorr());
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_schnorr_p256() {
+        run(&crate::P256ZKP::p256().schnorr());
+    }
+
+    // A Chaum-Pedersen proof for the same x and alpha carries a valid-looking
+    // (r1, s), but its challenge is hashed under another label
+    #[test]
+    fn test_separated_from_chaum_pedersen() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let schnorr = zkp.schnorr();
+        let x = zkp.generate_secret_key();
+        let y = schnorr.check_element("y", schnorr.public_key(&x)).unwrap();
+        let cp_proof = zkp.prove_non_interactive(&x, b"alice");
+        let proof = SchnorrProof {
+            r: cp_proof.r1,
+            s: cp_proof.s,
+        };
+        assert_eq!(
+            schnorr.verify_non_interactive(&y, &proof, b"alice"),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_proofs() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let schnorr = zkp.schnorr();
+        let group = &schnorr.group;
+        let x = schnorr.generate_secret_key();
+        let y = schnorr.check_element("y", schnorr.public_key(&x)).unwrap();
+        let proof = schnorr.prove_non_interactive(&x, b"alice");
+
+        let other_q = &group.q + 2u32;
+        let wrong_q = SchnorrProof {
+            s: Scalar::new(proof.s.value().clone(), &other_q),
+            ..proof.clone()
+        };
+        assert_eq!(
+            schnorr.verify_non_interactive(&y, &wrong_q, b"alice"),
+            Err(ZkpError::ScalarOutOfRange("s"))
+        );
+        let identity = SchnorrProof {
+            r: group.identity(),
+            ..proof.clone()
+        };
+        assert_eq!(
+            schnorr.verify_non_interactive(&y, &identity, b"alice"),
+            Err(ZkpError::IdentityElement("r"))
+        );
+        let verifier = SchnorrVerifier::new(&schnorr, y.clone());
+        assert_eq!(
+            verifier.challenge(group.identity()).map(|_| ()),
+            Err(ZkpError::IdentityElement("r"))
+        );
+
+        let mut bytes = proof.to_bytes(group);
+        assert_eq!(SchnorrProof::from_bytes(group, &bytes), Ok(proof));
+        bytes.push(0);
+        assert_eq!(
+            SchnorrProof::from_bytes(group, &bytes),
+            Err(ZkpError::Decoding("proof"))
+        );
+        let p_bytes = group.element_to_bytes(&group.p);
+        let bytes = encode_fields(&[p_bytes, group.scalar_to_bytes(&wrong_q.s)]);
+        assert_eq!(
+            SchnorrProof::from_bytes(group, &bytes),
+            Err(ZkpError::Decoding("r"))
+        );
+    }
+
+    #[test]
+    fn test_shares_parameters() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let schnorr = zkp.schnorr();
+        let x = SecretKey::new(zkp.group.scalar(BigUint::from(6u32)));
+        assert_eq!(schnorr.public_key(&x), zkp.exponentiate(&x).0);
+
+        // the Schnorr proof is one element shorter
+        let proof = schnorr.prove_non_interactive(&x, b"alice");
+        let cp_proof = zkp.prove_non_interactive(&x, b"alice");
+        let r2 = encode_fields(&[zkp.group.element_to_bytes(&cp_proof.r2)]);
+        assert_eq!(
+            proof.to_bytes(&schnorr.group).len() + r2.len(),
+            cp_proof.to_bytes(&zkp.group).len()
+        );
+
+        // derived nonces are bound to the Schnorr statement
+        let deterministic = |x| {
+            let mode = NonceMode::Deterministic;
+            schnorr.prove_non_interactive_with_mode(x, b"alice", mode, &mut rand::thread_rng())
+        };
+        assert_eq!(deterministic(&x), deterministic(&x));
+        let other = SecretKey::new(zkp.group.scalar(BigUint::from(7u32)));
+        assert_ne!(deterministic(&x).r, deterministic(&other).r);
+
+        // but the interactive prover never repeats a commitment
+        let commit = || {
+            let prover = SchnorrProver::new(&schnorr, SecretKey::new(x.expose().clone()));
+            prover.with_nonce_mode(NonceMode::Deterministic).commit().0
+        };
+        assert_ne!(commit(), commit());
+    }
+
+    #[test]
+    fn test_from_group_checks_alpha() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let from_group = |alpha| Schnorr::from_group(zkp.group.clone(), alpha).map(|_| ());
+        assert_eq!(from_group(zkp.alpha.clone()), Ok(()));
+        assert_eq!(
+            from_group(zkp.group.identity()),
+            Err(ZkpError::IdentityElement("alpha"))
+        );
+        assert_eq!(
+            from_group(&zkp.group.p - 1u32),
+            Err(ZkpError::NotInSubgroup("alpha"))
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::group::Group;
+
+/// The prover's long-term secret x, with y1 = alpha^x and y2 = beta^x.
+///
+/// The scalar is wiped when the key is dropped and is never printed. It
+/// cannot be read back or cloned, only passed to the prover methods of
+/// `ChaumPedersen`.
+pub struct SecretKey<G: Group>(G::Scalar);
+
+/// The nonce k behind one commitment (r1, r2) = (alpha^k, beta^k).
+///
+/// Anyone who learns k and the response s learns x, so it gets the same
+/// treatment as `SecretKey`.
+pub struct Nonce<G: Group>(G::Scalar);
+
+macro_rules! secret_scalar {
+    ($name:ident) => {
+        impl<G: Group> $name<G> {
+            // Take ownership of the scalar, so no other copy is left behind
+            pub fn new(scalar: G::Scalar) -> Self {
+                $name(scalar)
+            }
+
+            // Uniformly random in Z_q
+            pub(crate) fn random<R: RngCore + CryptoRng>(group: &G, rng: &mut R) -> Self {
+                $name(group.random_scalar(rng))
+            }
+
+            pub(crate) fn expose(&self) -> &G::Scalar {
+                &self.0
+            }
+        }
+
+        impl<G: Group> Drop for $name<G> {
+            fn drop(&mut self) {
+                G::zeroize_scalar(&mut self.0);
+            }
+        }
+
+        impl<G: Group> fmt::Debug for $name<G> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), "(..)"))
+            }
+        }
+    };
+}
+
+secret_scalar!(SecretKey);
+secret_scalar!(Nonce);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ModPGroup, ParamSet};
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let scalar = ParamSet::Rfc5114_1024_160
+            .zkp()
+            .unwrap()
+            .group
+            .scalar(BigUint::from(1234567u32));
+        let x = SecretKey::<ModPGroup>::new(scalar.clone());
+        assert_eq!(format!("{:?}", x), "SecretKey(..)");
+        let k = Nonce::<ModPGroup>::new(scalar);
+        assert_eq!(format!("{:?}", k), "Nonce(..)");
+    }
+
+    #[test]
+    fn test_zeroize_scalar() {
+        let group = ParamSet::Rfc5114_1024_160.zkp().unwrap().group;
+        let mut x = group.scalar(BigUint::from_bytes_be(&[0xab; 100]));
+        ModPGroup::zeroize_scalar(&mut x);
+        assert_eq!(x, group.scalar(BigUint::from(0u32)));
+    }
+}
@@ -1,37 +1,35 @@
-use num_bigint::BigUint;
 use rand::Rng;
-use std::{collections::HashMap, sync::Mutex};
+use std::{collections::HashMap, fmt, sync::Mutex, sync::PoisonError};
 use tonic::{transport::Server, Code, Request, Response, Status};
-use zkp_chaum_pedersen::ZKP;
+use zkp_chaum_pedersen::{DynAwaitingResponse, ParamSet, ZkpError};
 
-
-fn _alpha() -> BigUint {
-    let (alpha, _beta, _p, _q, _rng_upper_bound) = ZKP::get_1024_bits_config();
-    alpha
-}
-fn _beta() -> BigUint {
-    let (_alpha, beta, _p, _q, _rng_upper_bound) = ZKP::get_1024_bits_config();
-    beta
-}
-
-fn _p() -> BigUint {
-    let (__alpha, __beta, p, _q, __rng_upper_bound) = ZKP::get_1024_bits_config();
-    p
-}
-
-fn _q() -> BigUint {
-    let (_alpha, _beta, _p, q, _rng_upper_bound) = ZKP::get_1024_bits_config();
-    q
+// Parameter set a user registered with, empty means the RFC 5114 1024-bit group
+fn param_set(group: &str) -> Result<ParamSet, ZkpError> {
+    if group.is_empty() {
+        return Ok(ParamSet::Rfc5114_1024_160);
+    }
+    ParamSet::from_id(group).ok_or_else(|| ZkpError::UnsupportedGroup(group.to_string()))
 }
 
-fn _rng_upper_bound() -> BigUint {
-    let (_alpha, _beta, _p, _q, rng_upper_bound) = ZKP::get_1024_bits_config();
-    rng_upper_bound
+// gRPC status for a failure of the protocol, logged with its reason
+fn zkp_status(err: ZkpError) -> Status {
+    println!("Rejected: {}", err);
+    let code = match err {
+        ZkpError::VerificationFailed => Code::Unauthenticated,
+        ZkpError::InvalidParameters(_) => Code::FailedPrecondition,
+        ZkpError::UnsupportedGroup(_)
+        | ZkpError::Decoding(_)
+        | ZkpError::ScalarOutOfRange(_)
+        | ZkpError::NotInSubgroup(_)
+        | ZkpError::IdentityElement(_)
+        | ZkpError::LengthMismatch(_) => Code::InvalidArgument,
+    };
+    Status::new(code, err.to_string())
 }
 
-fn zkp_instance() -> ZKP {
-    let (alpha, beta, p, q, rng_upper_bound) = ZKP::get_1024_bits_config();
-    ZKP::new(alpha, beta, p, q, rng_upper_bound)
+// A handler panicked while holding the lock; report it instead of panicking too
+fn poisoned<T>(_: PoisonError<T>) -> Status {
+    Status::new(Code::Internal, "server state is poisoned")
 }
 pub mod zkp_auth {
     include!("./zkp_auth.rs");
@@ -55,25 +53,38 @@ fn generate_random_string(size: usize) -> String {
 #[derive(Debug, Default)]
 pub struct AuthImpl {
     pub user_info: Mutex<HashMap<String, UserInfo>>,
-    pub auth_id_to_user: Mutex<HashMap<String, String>>,
+    // open challenges by auth_id, each answered at most once
+    pub challenges: Mutex<HashMap<String, Challenge>>,
 }
 
 #[derive(Debug, Default)]
 
 pub struct UserInfo {
     pub user_name: String,
+    // parameter set id the user registered with, values below are encoded in it
+    pub group: String,
+    // salt the client derived x with
+    pub salt: Vec<u8>,
     // registration
-    pub y1: BigUint,
-    pub y2: BigUint,
-    // authorization
-    pub r1: BigUint,
-    pub r2: BigUint,
-    //verification
-    pub c: BigUint,
-    pub s: BigUint,
+    pub y1: Vec<u8>,
+    pub y2: Vec<u8>,
     pub session_id: String,
 }
 
+// The verifier waiting for the answer to a challenge it sent to user_name
+pub struct Challenge {
+    pub user_name: String,
+    pub verifier: Box<dyn DynAwaitingResponse>,
+}
+
+impl fmt::Debug for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Challenge")
+            .field("user_name", &self.user_name)
+            .finish_non_exhaustive()
+    }
+}
+
 // Main function to start the gRPC server
 #[tonic::async_trait]
 impl Auth for AuthImpl {
@@ -85,13 +96,22 @@ impl Auth for AuthImpl {
         let request = request.into_inner();
         let user_name = request.user_name;
 
-        let mut user_info_cache = UserInfo::default();
-        user_info_cache.user_name = user_name.clone();
-        user_info_cache.y1 = BigUint::from_bytes_be(&request.y1);
-        user_info_cache.y2 = BigUint::from_bytes_be(&request.y2);
+        let set = param_set(&request.group).map_err(zkp_status)?;
+        let zkp = set.protocol();
+        zkp.check_encoded("y1", &request.y1).map_err(zkp_status)?;
+        zkp.check_encoded("y2", &request.y2).map_err(zkp_status)?;
+
+        let user_info_cache = UserInfo {
+            user_name: user_name.clone(),
+            group: set.id().to_string(),
+            salt: request.salt,
+            y1: request.y1,
+            y2: request.y2,
+            ..Default::default()
+        };
 
         // servers should not panick
-        let user_info_hashmap = &mut self.user_info.lock().unwrap();
+        let user_info_hashmap = &mut self.user_info.lock().map_err(poisoned)?;
         user_info_hashmap.insert(user_name, user_info_cache);
 
         Ok(Response::new(RegisterResponse {}))
@@ -106,20 +126,28 @@ impl Auth for AuthImpl {
 
         let user_name = request.user_name;
 
-        let user_info_hashmap = &mut self.user_info.lock().unwrap();
+        // user_info before challenges, as in verify_authentication
+        let user_info_hashmap = &mut self.user_info.lock().map_err(poisoned)?;
 
-        if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
-            user_info.r1 = BigUint::from_bytes_be(&request.r1);
-            user_info.r2 = BigUint::from_bytes_be(&request.r2);
+        if let Some(user_info) = user_info_hashmap.get(&user_name) {
+            let zkp = param_set(&user_info.group).map_err(zkp_status)?.protocol();
+            let (c, verifier) = zkp
+                .challenge_encoded(&user_info.y1, &user_info.y2, &request.r1, &request.r2)
+                .map_err(zkp_status)?;
 
-            let c = zkp_instance().generate_random();
-            user_info.c = c.clone();
             let auth_id = generate_random_string(48);
-            let auth_id_to_user = &mut self.auth_id_to_user.lock().unwrap();
-            auth_id_to_user.insert(auth_id.clone(), user_name);
+            let challenges = &mut self.challenges.lock().map_err(poisoned)?;
+            challenges.insert(
+                auth_id.clone(),
+                Challenge {
+                    user_name,
+                    verifier,
+                },
+            );
             return Ok(Response::new(AuthenticationChallengeResponse {
                 auth_id,
-                c: c.to_bytes_be(),
+                c,
+                salt: user_info.salt.clone(),
             }));
         } else {
             return Err(Status::new(
@@ -137,40 +165,37 @@ impl Auth for AuthImpl {
         let request = request.into_inner();
 
         let auth_id = request.auth_id;
-        let s = BigUint::from_bytes_be(&request.s);
-
-        let auth_id_hashmap = &mut self.auth_id_to_user.lock().unwrap();
-
-        if let Some(user_name) = auth_id_hashmap.get_mut(&auth_id) {
-            let user_info_hashmap = &mut self.user_info.lock().unwrap();
-            let user_info = user_info_hashmap.get_mut(&user_name.clone()).unwrap();
-
-            match zkp_instance().verify(
-                &user_info.y1,
-                &user_info.y2,
-                &user_info.r1,
-                &user_info.r2,
-                &s,
-                &user_info.c,
-            ) {
-                true => {
-                    let session_id = generate_random_string(48);
-                    user_info.session_id = session_id.clone();
-                    return Ok(Response::new(AuthenticationAnswerResponse { session_id }));
-                }
-                _ => {
-                    return Err(Status::new(
-                        Code::NotFound,
-                        format!("S : {} wrong answer", s),
-                    ));
-                }
-            }
-        } else {
-            return Err(Status::new(
-                Code::NotFound,
-                format!("AuthId : {} not found in database", auth_id),
-            ));
-        }
+        let s = request.s;
+
+        let user_info_hashmap = &mut self.user_info.lock().map_err(poisoned)?;
+        // Removed whether or not the answer verifies, so it cannot be replayed
+        let challenge = self
+            .challenges
+            .lock()
+            .map_err(poisoned)?
+            .remove(&auth_id)
+            .ok_or_else(|| {
+                Status::new(
+                    Code::NotFound,
+                    format!("AuthId : {} not found in database", auth_id),
+                )
+            })?;
+
+        let user_name = challenge.user_name;
+        let user_info = user_info_hashmap
+            .get_mut(user_name.as_str())
+            .ok_or_else(|| {
+                Status::new(
+                    Code::NotFound,
+                    format!("User : {} not found in database", user_name),
+                )
+            })?;
+
+        challenge.verifier.verify_encoded(&s).map_err(zkp_status)?;
+
+        let session_id = generate_random_string(48);
+        user_info.session_id = session_id.clone();
+        Ok(Response::new(AuthenticationAnswerResponse { session_id }))
     }
 }
 
@@ -181,9 +206,19 @@ async fn main() {
 
     let auth_impl = AuthImpl::default();
 
-    Server::builder()
+    let addr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(err) => {
+            eprintln!("Invalid address {addr} : {err}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(err) = Server::builder()
         .add_service(AuthServer::new(auth_impl))
-        .serve(addr.parse().unwrap())
+        .serve(addr)
         .await
-        .unwrap();
+    {
+        eprintln!("Server failed : {err}");
+        std::process::exit(1);
+    }
 }
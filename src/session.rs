@@ -0,0 +1,391 @@
+use std::fmt;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::group::Group;
+use crate::protocol::Sigma;
+use crate::{ChaumPedersen, GroupElement, Nonce, NonceMode, Protocol, SecretKey, ZkpError};
+
+/// The prover's first message: (r1, r2) = (alpha^k, beta^k).
+pub struct Commitment<G: Group> {
+    pub r1: G::Element,
+    pub r2: G::Element,
+}
+
+/// The prover's answer s = k - c * x mod q to a challenge c.
+pub struct Response<G: Group> {
+    pub s: G::Scalar,
+}
+
+impl<G: Group> Clone for Commitment<G> {
+    fn clone(&self) -> Self {
+        Commitment {
+            r1: self.r1.clone(),
+            r2: self.r2.clone(),
+        }
+    }
+}
+
+impl<G: Group> PartialEq for Commitment<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r1 == other.r1 && self.r2 == other.r2
+    }
+}
+
+impl<G: Group> fmt::Debug for Commitment<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Commitment")
+            .field("r1", &self.r1)
+            .field("r2", &self.r2)
+            .finish()
+    }
+}
+
+impl<G: Group> Clone for Response<G> {
+    fn clone(&self) -> Self {
+        Response { s: self.s.clone() }
+    }
+}
+
+impl<G: Group> fmt::Debug for Response<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response").field("s", &self.s).finish()
+    }
+}
+
+/// Prover side of one interactive run, holding the secret x.
+///
+/// Generic over the protocol, Chaum-Pedersen by default: the same states
+/// run Schnorr and DLEQ, see `SchnorrProver` and `DleqProver`.
+///
+/// `commit` draws a fresh nonce and hands it to `AwaitingChallenge`, whose
+/// `respond` consumes it, so each nonce answers exactly one challenge and
+/// no response can be computed before the commitment is sent:
+///
+/// ```compile_fail
+/// # use zkp_chaum_pedersen::{ParamSet, Protocol, Prover};
+/// let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+/// let (_, prover) = Prover::new(&zkp, zkp.generate_secret_key()).commit();
+/// let c = zkp.generate_random();
+/// prover.respond(&c);
+/// prover.respond(&c); // the nonce is gone
+/// ```
+pub struct Prover<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    x: SecretKey<G>,
+    mode: NonceMode,
+    context: Vec<u8>,
+}
+
+/// A prover that sent its commitment and waits for the challenge.
+pub struct AwaitingChallenge<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    x: SecretKey<G>,
+    k: Nonce<G>,
+}
+
+impl<'a, G: Group, P: Protocol<G>> Prover<'a, G, P> {
+    pub fn new(zkp: &'a P, x: SecretKey<G>) -> Self {
+        Prover {
+            zkp,
+            x,
+            mode: NonceMode::Random,
+            context: Vec::new(),
+        }
+    }
+
+    // How `commit` draws the nonce, `NonceMode::Random` by default.
+    // `NonceMode::Deterministic` is hedged, since the verifier picks c
+    pub fn with_nonce_mode(mut self, mode: NonceMode) -> Self {
+        self.mode = mode.interactive();
+        self
+    }
+
+    // Bytes bound into derived nonces, such as the user name and a session id
+    pub fn with_context(mut self, context: &[u8]) -> Self {
+        self.context = context.to_vec();
+        self
+    }
+
+    // The public key to register with the verifier: (y1, y2) for
+    // Chaum-Pedersen, y for Schnorr, h_1..h_n for DLEQ
+    pub fn public_key(&self) -> P::PublicKey<G::Element> {
+        P::public_key_from(self.zkp.exp_bases(self.x.expose()))
+    }
+
+    pub fn commit(self) -> (P::Commitment, AwaitingChallenge<'a, G, P>) {
+        self.commit_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+    ) -> (P::Commitment, AwaitingChallenge<'a, G, P>) {
+        let k = self
+            .zkp
+            .nonce_with_mode(self.mode, &self.x, &self.context, rng);
+        let r = self.zkp.exp_bases(k.expose());
+        let next = AwaitingChallenge {
+            zkp: self.zkp,
+            x: self.x,
+            k,
+        };
+        (P::commitment_from(r), next)
+    }
+}
+
+impl<G: Group, P: Protocol<G>> AwaitingChallenge<'_, G, P> {
+    // Answer the challenge, wiping the nonce
+    pub fn respond(self, c: &G::Scalar) -> Response<G> {
+        Response {
+            s: self.zkp.solve(&self.k, c, &self.x),
+        }
+    }
+}
+
+/// Prover side of a run where x is only known after the commitment, as
+/// when a client derives x from a salt the verifier sends back with c.
+///
+/// There is no x to derive the nonce from, so it is always drawn from the
+/// rng. As with `Prover`, each nonce answers exactly one challenge.
+pub struct DeferredProver<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    group: std::marker::PhantomData<G>,
+}
+
+/// A deferred prover that sent its commitment and waits for the challenge
+/// and the secret.
+pub struct DeferredAwaitingChallenge<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    k: Nonce<G>,
+}
+
+impl<'a, G: Group, P: Protocol<G>> DeferredProver<'a, G, P> {
+    pub fn new(zkp: &'a P) -> Self {
+        DeferredProver {
+            zkp,
+            group: std::marker::PhantomData,
+        }
+    }
+
+    pub fn commit(self) -> (P::Commitment, DeferredAwaitingChallenge<'a, G, P>) {
+        self.commit_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+    ) -> (P::Commitment, DeferredAwaitingChallenge<'a, G, P>) {
+        let k = Nonce::random(self.zkp.group(), rng);
+        let r = self.zkp.exp_bases(k.expose());
+        let next = DeferredAwaitingChallenge { zkp: self.zkp, k };
+        (P::commitment_from(r), next)
+    }
+}
+
+impl<G: Group, P: Protocol<G>> DeferredAwaitingChallenge<'_, G, P> {
+    // Answer the challenge with the now known x, wiping the nonce
+    pub fn respond(self, c: &G::Scalar, x: &SecretKey<G>) -> Response<G> {
+        Response {
+            s: self.zkp.solve(&self.k, c, x),
+        }
+    }
+}
+
+/// Verifier side of one interactive run against a registered public key.
+///
+/// Each state is consumed by the next, so a challenge is issued once per
+/// commitment and checked against exactly one response.
+pub struct Verifier<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    h: Vec<GroupElement<G>>,
+}
+
+/// A verifier that issued its challenge and waits for the response.
+pub struct AwaitingResponse<'a, G: Group, P: Protocol<G> = ChaumPedersen<G>> {
+    zkp: &'a P,
+    h: Vec<GroupElement<G>>,
+    r: Vec<GroupElement<G>>,
+    c: G::Scalar,
+}
+
+impl<'a, G: Group, P: Protocol<G>> Verifier<'a, G, P> {
+    // The public key as checked on registration, in the shape `Prover::public_key`
+    // gives it
+    pub fn new(zkp: &'a P, public_key: P::PublicKey<GroupElement<G>>) -> Self {
+        Verifier {
+            zkp,
+            h: P::public_key_values(public_key),
+        }
+    }
+
+    // Check the commitment and draw the challenge to send back
+    pub fn challenge(
+        self,
+        commitment: P::Commitment,
+    ) -> Result<(G::Scalar, AwaitingResponse<'a, G, P>), ZkpError> {
+        self.challenge_with_rng(commitment, &mut rand::thread_rng())
+    }
+
+    pub fn challenge_with_rng<R: RngCore + CryptoRng>(
+        self,
+        commitment: P::Commitment,
+        rng: &mut R,
+    ) -> Result<(G::Scalar, AwaitingResponse<'a, G, P>), ZkpError> {
+        let r = self.zkp.check_commitment(commitment)?;
+        let c = self.zkp.generate_random_with_rng(rng);
+        let next = AwaitingResponse {
+            zkp: self.zkp,
+            h: self.h,
+            r,
+            c: c.clone(),
+        };
+        Ok((c, next))
+    }
+}
+
+impl<G: Group, P: Protocol<G>> AwaitingResponse<'_, G, P> {
+    pub fn verify(self, response: &Response<G>) -> Result<(), ZkpError> {
+        let h: Vec<&GroupElement<G>> = self.h.iter().collect();
+        let r: Vec<&GroupElement<G>> = self.r.iter().collect();
+        self.zkp.verify_values(&h, &r, &response.s, &self.c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ParamSet;
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // (y1, y2) as the verifier receives them
+    fn public_key<G: Group>(
+        zkp: &ChaumPedersen<G>,
+        prover: &Prover<'_, G>,
+    ) -> (GroupElement<G>, GroupElement<G>) {
+        let (y1, y2) = prover.public_key();
+        (
+            zkp.check_element("y1", y1).unwrap(),
+            zkp.check_element("y2", y2).unwrap(),
+        )
+    }
+
+    fn run_session<G: Group>(zkp: &ChaumPedersen<G>) {
+        let prover = Prover::new(zkp, zkp.generate_secret_key());
+        let (y1, y2) = public_key(zkp, &prover);
+
+        let verifier = Verifier::new(zkp, (y1.clone(), y2.clone()));
+        let (commitment, prover) = prover.commit();
+        let (c, verifier) = verifier.challenge(commitment).unwrap();
+        let response = prover.respond(&c);
+        assert_eq!(verifier.verify(&response), Ok(()));
+
+        // someone who does not know x
+        let impostor = Prover::new(zkp, zkp.generate_secret_key());
+        let verifier = Verifier::new(zkp, (y1, y2));
+        let (commitment, impostor) = impostor.commit();
+        let (c, verifier) = verifier.challenge(commitment).unwrap();
+        assert_eq!(
+            verifier.verify(&impostor.respond(&c)),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_session_modp() {
+        run_session(&ParamSet::Rfc5114_1024_160.zkp().unwrap());
+    }
+
+    #[cfg(feature = "ristretto")]
+    #[test]
+    fn test_session_ristretto() {
+        run_session(&crate::RistrettoZKP::ristretto255());
+    }
+
+    // the commitment is sent before x is known, as in the client's login
+    #[test]
+    fn test_deferred_prover() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let x = zkp.generate_secret_key();
+        let (y1, y2) = zkp.exponentiate(&x);
+        let y1 = zkp.check_element("y1", y1).unwrap();
+        let y2 = zkp.check_element("y2", y2).unwrap();
+        let login = |x: &SecretKey<_>| {
+            let verifier = Verifier::new(&zkp, (y1.clone(), y2.clone()));
+            let (commitment, prover) = DeferredProver::new(&zkp).commit();
+            let (c, verifier) = verifier.challenge(commitment).unwrap();
+            verifier.verify(&prover.respond(&c, x))
+        };
+        assert_eq!(login(&x), Ok(()));
+        assert_eq!(
+            login(&zkp.generate_secret_key()),
+            Err(ZkpError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_rejects_elements_outside_the_group() {
+        let zkp = ParamSet::Rfc5114_1024_160.zkp().unwrap();
+        let outside = &zkp.group.p - 1u32;
+        let y1 = zkp.check_element("y1", zkp.alpha.clone()).unwrap();
+        let y2 = zkp.check_element("y2", zkp.beta.clone()).unwrap();
+        let challenge = |r1: &BigUint, r2: &BigUint| {
+            let verifier = Verifier::new(&zkp, (y1.clone(), y2.clone()));
+            let commitment = Commitment {
+                r1: r1.clone(),
+                r2: r2.clone(),
+            };
+            verifier.challenge(commitment).map(|_| ())
+        };
+        assert_eq!(
+            challenge(&zkp.alpha, &outside),
+            Err(ZkpError::NotInSubgroup("r2"))
+        );
+        assert_eq!(
+            challenge(&BigUint::from(1u32), &zkp.beta),
+            Err(ZkpError::IdentityElement("r1"))
+        );
+    }
+
+    // Seeded runs, pinned so that any change to how randomness is consumed
+    // shows up here
+    fn seeded_session<G: Group>(zkp: &ChaumPedersen<G>) -> [String; 4] {
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let group = &zkp.group;
+        let prover = Prover::new(zkp, zkp.generate_secret_key_with_rng(&mut rng));
+        let (y1, y2) = public_key(zkp, &prover);
+        let verifier = Verifier::new(zkp, (y1.clone(), y2));
+        let (commitment, prover) = prover.commit_with_rng(&mut rng);
+        let r1 = commitment.r1.clone();
+        let (c, verifier) = verifier.challenge_with_rng(commitment, &mut rng).unwrap();
+        let response = prover.respond(&c);
+        assert_eq!(verifier.verify(&response), Ok(()));
+        [
+            hex::encode(group.element_to_bytes(y1.element())),
+            hex::encode(group.element_to_bytes(&r1)),
+            hex::encode(group.scalar_to_bytes(&c)),
+            hex::encode(group.scalar_to_bytes(&response.s)),
+        ]
+    }
+
+    #[test]
+    fn test_seeded_vectors() {
+        let [_, _, c, s] = seeded_session(&ParamSet::Rfc5114_1024_160.zkp().unwrap());
+        // challenges are drawn below the 128-bit challenge_bound
+        assert_eq!(c, "00000000ab54d1203f8ad70376995a92605d85ab");
+        assert_eq!(s, "b6ba98de75c09f4ad53450ffb6fe83e64ec9fbf5");
+
+        #[cfg(feature = "ristretto")]
+        assert_eq!(
+            seeded_session(&crate::RistrettoZKP::ristretto255()),
+            [
+                "ca3f9db3b7808bdd87faac3639b0da197e9b9daddd37973e2984586a24018e2a",
+                "a6f2456958fa0b3644dc4a438cb00de0279e740e4c38854791ffea56a4afe346",
+                "57c6cad5b580f5d01c0159c42ca025ddc5c326b954b6a0ddc62829b1fc78be0d",
+                "81ad523adc382a8c95c44a23f6c61aa4c0b12dbcd53a79619de995332daf990f",
+            ]
+        );
+    }
+}
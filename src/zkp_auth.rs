@@ -1,6 +1,11 @@
 /// Prover registers in the server sending:
 /// y1 = alpha^x mod p
 /// y2 = beta^x mod p
+/// and the id of the parameter set the values live in, e.g. "rfc5114-1024-160",
+/// "rfc5114-2048-256", "ffdhe2048", "ristretto255", "p256" or "secp256k1".
+/// Curve points are SEC1 compressed. Empty means "rfc5114-1024-160".
+/// The salt is the one x was derived from the password with, the server keeps
+/// it and hands it back with every challenge.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterRequest {
@@ -10,6 +15,10 @@ pub struct RegisterRequest {
     pub y1: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "3")]
     pub y2: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "4")]
+    pub group: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "5")]
+    pub salt: ::prost::alloc::vec::Vec<u8>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -17,7 +26,8 @@ pub struct RegisterResponse {}
 /// Prover asks for a challenge in the server sending
 /// r1 = alpha^k mod p
 /// r2 = beta^k mod p
-/// Verifier sends the challenge "c" back
+/// Verifier sends the challenge "c" back, with the user's salt so the prover
+/// can derive x from the password again
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AuthenticationChallengeRequest {
@@ -35,6 +45,8 @@ pub struct AuthenticationChallengeResponse {
     pub auth_id: ::prost::alloc::string::String,
     #[prost(bytes = "vec", tag = "2")]
     pub c: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub salt: ::prost::alloc::vec::Vec<u8>,
 }
 /// Prover sends the solution "s = k - c * x mod q" to the challenge
 /// Verifier sends the session ID if the solution is correct